@@ -1251,7 +1251,7 @@ impl<'a> Builder<'a> {
 #[cfg(test)]
 mod __test {
     use super::*;
-    use config::Config;
+    use config::{Config, Target};
     use std::thread;
 
     fn configure(host: &[&str], target: &[&str]) -> Config {
@@ -1866,6 +1866,31 @@ mod __test {
         );
     }
 
+    #[test]
+    fn runner_replaces_remote_testing() {
+        let b = INTERNER.intern_str("B");
+        let qemu = || Target {
+            qemu_rootfs: Some(PathBuf::from("/qemu-rootfs")),
+            ..Target::default()
+        };
+
+        let mut config = configure(&[], &["B"]);
+        config.target_config.insert(b, qemu());
+        let build = Build::new(config);
+        assert!(build.remote_tested(b));
+        assert_eq!(build.runner(b), None);
+
+        let runner = vec!["qemu-arm".to_string(), "-L".to_string(), "/usr/arm linux".to_string()];
+        let mut config = configure(&[], &["B"]);
+        config.target_config.insert(b, Target {
+            runner: Some(runner.clone()),
+            ..qemu()
+        });
+        let build = Build::new(config);
+        assert!(!build.remote_tested(b));
+        assert_eq!(build.runner(b), Some(&runner[..]));
+    }
+
     #[test]
     fn test_exclude() {
         let mut config = configure(&[], &[]);
@@ -174,6 +174,8 @@ pub struct Target {
     pub crt_static: Option<bool>,
     pub musl_root: Option<PathBuf>,
     pub qemu_rootfs: Option<PathBuf>,
+    /// The program test binaries are run under, followed by its arguments.
+    pub runner: Option<Vec<String>>,
     pub no_std: bool,
 }
 
@@ -344,6 +346,7 @@ struct TomlTarget {
     crt_static: Option<bool>,
     musl_root: Option<String>,
     qemu_rootfs: Option<String>,
+    runner: Option<Vec<String>>,
 }
 
 impl Config {
@@ -600,6 +603,7 @@ impl Config {
                 target.crt_static = cfg.crt_static.clone();
                 target.musl_root = cfg.musl_root.clone().map(PathBuf::from);
                 target.qemu_rootfs = cfg.qemu_rootfs.clone().map(PathBuf::from);
+                target.runner = cfg.runner.clone().filter(|runner| !runner.is_empty());
 
                 config.target_config.insert(INTERNER.intern_string(triple.clone()), target);
             }
@@ -190,7 +190,7 @@ To learn more about a subcommand, run `./x.py <subcommand> -h`"
                 opts.optflag(
                     "",
                     "bless",
-                    "update all stderr/stdout files of failing ui tests",
+                    "update all expected output files of failing ui, mir-opt and pretty tests",
                 );
                 opts.optopt(
                     "",
@@ -295,6 +295,7 @@ Arguments:
         ./x.py test src/libstd --test-args hash_map
         ./x.py test src/libstd --stage 0 --no-doc
         ./x.py test src/test/ui --bless
+        ./x.py test src/test/mir-opt --bless
         ./x.py test src/test/ui --compare-mode nll
 
     Note that `test src/test/* --stage N` does NOT depend on `build src/rustc --stage N`;
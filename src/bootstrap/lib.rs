@@ -870,10 +870,12 @@ impl Build {
     }
 
     /// Returns whether the target will be tested using the `remote-test-client`
-    /// and `remote-test-server` binaries.
+    /// and `remote-test-server` binaries. Targets with a runner are tested
+    /// locally under it instead.
     fn remote_tested(&self, target: Interned<String>) -> bool {
-        self.qemu_rootfs(target).is_some() || target.contains("android") ||
-        env::var_os("TEST_DEVICE_ADDR").is_some()
+        self.runner(target).is_none() &&
+        (self.qemu_rootfs(target).is_some() || target.contains("android") ||
+         env::var_os("TEST_DEVICE_ADDR").is_some())
     }
 
     /// Returns the root of the "rootfs" image that this target will be using,
@@ -887,6 +889,15 @@ impl Build {
             .map(|p| &**p)
     }
 
+    /// Returns the command that test binaries for this target are run under
+    /// locally, such as an emulator, if one was configured: the program
+    /// followed by its arguments.
+    fn runner(&self, target: Interned<String>) -> Option<&[String]> {
+        self.config.target_config.get(&target)
+            .and_then(|t| t.runner.as_ref())
+            .map(|r| &**r)
+    }
+
     /// Path to the python interpreter to use
     fn python(&self) -> &Path {
         self.config.python.as_ref().unwrap()
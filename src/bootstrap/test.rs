@@ -1189,7 +1189,12 @@ impl Step for Compiletest {
                 .arg("");
         }
 
-        if builder.remote_tested(target) {
+        if let Some(runner) = builder.runner(target) {
+            cmd.arg("--runner").arg(&runner[0]);
+            for arg in &runner[1..] {
+                cmd.arg("--runner-arg").arg(arg);
+            }
+        } else if builder.remote_tested(target) {
             cmd.arg("--remote-test-client")
                 .arg(builder.tool_exe(Tool::RemoteTestClient));
         }
@@ -1707,6 +1712,10 @@ impl Step for Crate {
                 builder.src.display()
             );
             cargo.env(format!("CARGO_TARGET_{}_RUNNER", envify(&target)), &runner);
+        } else if let Some(runner) = builder.runner(target) {
+            // Cargo splits the runner on spaces, which is documented as a
+            // restriction of the `runner` option.
+            cargo.env(format!("CARGO_TARGET_{}_RUNNER", envify(&target)), runner.join(" "));
         } else if builder.remote_tested(target) {
             cargo.env(
                 format!("CARGO_TARGET_{}_RUNNER", envify(&target)),
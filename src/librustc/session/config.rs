@@ -58,6 +58,15 @@ pub enum Sanitizer {
     Thread,
 }
 
+/// How `-Z apply-suggestions` applies the machine-applicable suggestions of the compilation.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ApplySuggestions {
+    /// Rewrite the source files, recompiling until there is nothing left to apply.
+    InPlace,
+    /// Print the changes as a unified diff without touching the source files.
+    DryRun,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Hash)]
 pub enum OptLevel {
     No,         // -O0
@@ -214,6 +223,8 @@ pub enum ErrorOutputType {
     HumanReadable(ColorConfig),
     Json(bool),
     Short(ColorConfig),
+    /// A single SARIF log of all the diagnostics, written at the end of the compilation.
+    Sarif,
 }
 
 impl Default for ErrorOutputType {
@@ -806,6 +817,8 @@ macro_rules! options {
             Some("one of: `full`, `partial`, or `off`");
         pub const parse_sanitizer: Option<&str> =
             Some("one of: `address`, `leak`, `memory` or `thread`");
+        pub const parse_apply_suggestions: Option<&str> =
+            Some("either `dry-run` or omitted");
         pub const parse_linker_flavor: Option<&str> =
             Some(::rustc_target::spec::LinkerFlavor::one_of());
         pub const parse_optimization_fuel: Option<&str> =
@@ -822,7 +835,7 @@ macro_rules! options {
 
     #[allow(dead_code)]
     mod $mod_set {
-        use super::{$struct_name, Passes, Sanitizer, LtoCli, CrossLangLto};
+        use super::{$struct_name, Passes, Sanitizer, LtoCli, CrossLangLto, ApplySuggestions};
         use rustc_target::spec::{LinkerFlavor, PanicStrategy, RelroLevel};
         use std::path::PathBuf;
 
@@ -981,6 +994,15 @@ macro_rules! options {
             true
         }
 
+        fn parse_apply_suggestions(slot: &mut Option<ApplySuggestions>, v: Option<&str>) -> bool {
+            match v {
+                None => *slot = Some(ApplySuggestions::InPlace),
+                Some("dry-run") => *slot = Some(ApplySuggestions::DryRun),
+                _ => return false,
+            }
+            true
+        }
+
         fn parse_linker_flavor(slote: &mut Option<LinkerFlavor>, v: Option<&str>) -> bool {
             match v.and_then(LinkerFlavor::from_str) {
                 Some(lf) => *slote = Some(lf),
@@ -1388,6 +1410,18 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "whether to use the PLT when calling into shared libraries;
           only has effect for PIC code on systems with ELF binaries
           (default: PLT is disabled if full relro is enabled)"),
+    apply_suggestions: Option<ApplySuggestions> = (None, parse_apply_suggestions, [UNTRACKED],
+        "apply machine-applicable suggestions to the source files (`=dry-run` prints a diff)"),
+    diagnostic_baseline: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "don't report the lints recorded in the given baseline file"),
+    record_diagnostic_baseline: bool = (false, parse_bool, [UNTRACKED],
+        "record the lints of the crate to the file given with `-Z diagnostic-baseline`"),
+    group_diagnostics: bool = (false, parse_bool, [UNTRACKED],
+        "report errors grouped by root cause, showing only the root causes"),
+    expand_diagnostic_groups: bool = (false, parse_bool, [UNTRACKED],
+        "report errors grouped by root cause, showing all of them"),
+    translation_bundle: Vec<PathBuf> = (Vec::new(), parse_pathbuf_push, [UNTRACKED],
+        "translate the diagnostic messages with the given bundle (may be given several times)"),
 }
 
 pub fn default_lib_output() -> CrateType {
@@ -1875,13 +1909,14 @@ pub fn build_session_options_and_crate_config(
             Some("json") => ErrorOutputType::Json(false),
             Some("pretty-json") => ErrorOutputType::Json(true),
             Some("short") => ErrorOutputType::Short(color),
+            Some("sarif") => ErrorOutputType::Sarif,
             None => ErrorOutputType::HumanReadable(color),
 
             Some(arg) => early_error(
                 ErrorOutputType::HumanReadable(color),
                 &format!(
-                    "argument for --error-format must be `human`, `json` or \
-                     `short` (instead was `{}`)",
+                    "argument for --error-format must be `human`, `json`, \
+                     `short` or `sarif` (instead was `{}`)",
                     arg
                 ),
             ),
@@ -1905,6 +1940,20 @@ pub fn build_session_options_and_crate_config(
         );
     }
 
+    if !debugging_opts.unstable_options && error_format == ErrorOutputType::Sarif {
+        early_error(
+            ErrorOutputType::HumanReadable(color),
+            "--error-format=sarif is unstable",
+        );
+    }
+
+    if debugging_opts.record_diagnostic_baseline && debugging_opts.diagnostic_baseline.is_none() {
+        early_error(
+            error_format,
+            "`-Z record-diagnostic-baseline` requires `-Z diagnostic-baseline`",
+        );
+    }
+
     if debugging_opts.pgo_gen.is_some() && !debugging_opts.pgo_use.is_empty() {
         early_error(
             error_format,
@@ -34,6 +34,7 @@ use syntax::ast::{self, NodeId};
 use syntax::edition::Edition;
 use syntax::feature_gate::{self, AttributeType};
 use syntax::json::JsonEmitter;
+use syntax::sarif::SarifEmitter;
 use syntax::source_map;
 use syntax::parse::{self, ParseSess};
 use syntax_pos::{MultiSpan, Span};
@@ -243,6 +244,14 @@ impl Session {
     ) -> DiagnosticBuilder<'a> {
         self.diagnostic().struct_span_err_with_code(sp, msg, code)
     }
+    pub fn struct_span_err_localized<'a, S: Into<MultiSpan>>(
+        &'a self,
+        sp: S,
+        msg: errors::LocalizedMessage,
+        code: DiagnosticId,
+    ) -> DiagnosticBuilder<'a> {
+        self.diagnostic().struct_span_err_localized(sp, msg, code)
+    }
     // FIXME: This method should be removed (every error should have an associated error code).
     pub fn struct_err<'a>(&'a self, msg: &str) -> DiagnosticBuilder<'a> {
         self.diagnostic().struct_err(msg)
@@ -1039,6 +1048,9 @@ pub fn build_session_with_source_map(
     let report_delayed_bugs = sopts.debugging_opts.report_delayed_bugs;
 
     let external_macro_backtrace = sopts.debugging_opts.external_macro_backtrace;
+    let collect_suggestions = sopts.debugging_opts.apply_suggestions.is_some();
+    let expand_diagnostic_groups = sopts.debugging_opts.expand_diagnostic_groups;
+    let group_diagnostics = sopts.debugging_opts.group_diagnostics || expand_diagnostic_groups;
 
     let emitter: Box<dyn Emitter + sync::Send> =
         match (sopts.error_format, emitter_dest) {
@@ -1075,9 +1087,15 @@ pub fn build_session_with_source_map(
             (config::ErrorOutputType::Short(_), Some(dst)) => {
                 Box::new(EmitterWriter::new(dst, Some(source_map.clone()), true, false))
             }
+            (config::ErrorOutputType::Sarif, None) => Box::new(
+                SarifEmitter::stderr(Some(registry), source_map.clone()),
+            ),
+            (config::ErrorOutputType::Sarif, Some(dst)) => Box::new(
+                SarifEmitter::new(dst, Some(registry), source_map.clone()),
+            ),
         };
 
-    let diagnostic_handler = errors::Handler::with_emitter_and_flags(
+    let mut diagnostic_handler = errors::Handler::with_emitter_and_flags(
         emitter,
         errors::HandlerFlags {
             can_emit_warnings,
@@ -1085,10 +1103,38 @@ pub fn build_session_with_source_map(
             report_delayed_bugs,
             dont_buffer_diagnostics,
             external_macro_backtrace,
+            collect_suggestions,
+            group_diagnostics,
+            expand_diagnostic_groups,
             ..Default::default()
         },
     );
 
+    if let Some(ref path) = sopts.debugging_opts.diagnostic_baseline {
+        let baseline = if sopts.debugging_opts.record_diagnostic_baseline {
+            errors::Baseline::record(path.clone(), source_map.clone())
+        } else {
+            errors::Baseline::load(path, source_map.clone()).unwrap_or_else(|e| {
+                early_error(sopts.error_format,
+                            &format!("couldn't read the diagnostic baseline `{}`: {}",
+                                     path.display(), e))
+            })
+        };
+        diagnostic_handler.set_baseline(baseline);
+    }
+
+    if !sopts.debugging_opts.translation_bundle.is_empty() {
+        let mut bundle = errors::TranslationBundle::new();
+        for path in &sopts.debugging_opts.translation_bundle {
+            bundle.load(path).unwrap_or_else(|e| {
+                early_error(sopts.error_format,
+                            &format!("couldn't read the translation bundle `{}`: {}",
+                                     path.display(), e))
+            });
+        }
+        diagnostic_handler.set_translation_bundle(bundle);
+    }
+
     build_session_(sopts, local_crate_source_file, diagnostic_handler, source_map)
 }
 
@@ -1319,8 +1365,10 @@ pub fn early_error(output: config::ErrorOutputType, msg: &str) -> ! {
         config::ErrorOutputType::Short(color_config) => {
             Box::new(EmitterWriter::stderr(color_config, None, true, false))
         }
+        config::ErrorOutputType::Sarif => Box::new(SarifEmitter::basic()),
     };
     let handler = errors::Handler::with_emitter(true, false, emitter);
+    emit_early_sarif_warnings(&handler);
     handler.emit(&MultiSpan::new(), msg, errors::Level::Fatal);
     errors::FatalError.raise();
 }
@@ -1334,11 +1382,30 @@ pub fn early_warn(output: config::ErrorOutputType, msg: &str) {
         config::ErrorOutputType::Short(color_config) => {
             Box::new(EmitterWriter::stderr(color_config, None, true, false))
         }
+        config::ErrorOutputType::Sarif => {
+            EARLY_SARIF_WARNINGS.with(|warnings| warnings.borrow_mut().push(msg.to_string()));
+            return;
+        }
     };
     let handler = errors::Handler::with_emitter(true, false, emitter);
     handler.emit(&MultiSpan::new(), msg, errors::Level::Warning);
 }
 
+thread_local! {
+    /// The warnings given by `early_warn` with `--error-format=sarif`. Each SARIF emitter writes
+    /// a whole log when it is dropped, so these are written by the emitter of the session that
+    /// reports the diagnostics of the compilation, or by the one of the early error that stops
+    /// the compilation before there is such a session.
+    static EARLY_SARIF_WARNINGS: RefCell<Vec<String>> = RefCell::new(vec![]);
+}
+
+/// Emits the warnings buffered by `early_warn` to `handler`.
+pub fn emit_early_sarif_warnings(handler: &errors::Handler) {
+    for msg in EARLY_SARIF_WARNINGS.with(|warnings| warnings.replace(vec![])) {
+        handler.emit(&MultiSpan::new(), &msg, errors::Level::Warning);
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub enum CompileIncomplete {
     Stopped,
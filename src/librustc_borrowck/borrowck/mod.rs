@@ -46,7 +46,7 @@ use rustc_data_structures::sync::Lrc;
 use std::hash::{Hash, Hasher};
 use syntax::ast;
 use syntax_pos::{MultiSpan, Span};
-use errors::{Applicability, DiagnosticBuilder, DiagnosticId};
+use errors::{Applicability, DiagnosticBuilder, DiagnosticId, LocalizedMessage};
 
 use rustc::hir;
 use rustc::hir::intravisit::{self, Visitor};
@@ -281,6 +281,15 @@ impl<'a, 'b, 'tcx: 'b> BorrowckErrors<'a> for &'a BorrowckCtxt<'b, 'tcx> {
         self.tcx.sess.struct_span_err_with_code(sp, msg, code)
     }
 
+    fn struct_span_err_localized<S: Into<MultiSpan>>(self,
+                                                     sp: S,
+                                                     msg: LocalizedMessage,
+                                                     code: DiagnosticId)
+                                                     -> DiagnosticBuilder<'a>
+    {
+        self.tcx.sess.struct_span_err_localized(sp, msg, code)
+    }
+
     fn struct_span_err<S: Into<MultiSpan>>(self,
                                            sp: S,
                                            msg: &str)
@@ -0,0 +1,403 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Applies the machine-applicable suggestions of the compiler to the source files, with
+//! `-Z apply-suggestions`.
+//!
+//! Before the actual compilation, the crate is checked without reporting any diagnostic, and the
+//! `MachineApplicable` suggestions of the diagnostics are applied to the files. Since a fix can
+//! reveal other problems, this is repeated until there is nothing left to apply, or with a warning
+//! after `MAX_PASSES` passes. When the edits of two suggestions overlap, the one whose edits come
+//! first is applied and the other one is left for the next pass, where it is suggested again if it
+//! still makes sense on the fixed code. The actual compilation then reports the diagnostics left
+//! as usual. The byte order mark of the files, which the source map strips, is kept.
+//!
+//! With `-Z apply-suggestions=dry-run`, the crate is only checked once, and the changes are
+//! printed as a unified diff instead of being written to the files.
+
+use std::cmp;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::panic;
+use std::path::{Path, PathBuf};
+
+use errors::{CodeSuggestion, FatalErrorMarker};
+use rustc::session::{self, early_error, early_warn};
+use rustc::session::config::{self, ApplySuggestions, Input};
+use rustc_data_structures::sync::Lrc;
+use rustc_lint;
+use rustc_metadata::cstore::CStore;
+use syntax::ast;
+use syntax::source_map::SourceMap;
+use syntax_pos::FileName;
+use syntax_pos::hygiene::SyntaxContext;
+
+use driver::{self, CompileController, Compilation};
+use {diagnostics_registry, get_codegen_backend, target_features};
+
+/// The most times the crate is checked, in case applying suggestions never settles.
+const MAX_PASSES: usize = 10;
+/// The number of unchanged lines shown around the changes of a diff.
+const CONTEXT_LINES: usize = 3;
+/// The byte order mark, which the source map strips from the files.
+const BOM: &[u8] = b"\xef\xbb\xbf";
+
+/// The replacement of the bytes `lo..hi` of a file by `snippet`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Edit {
+    lo: usize,
+    hi: usize,
+    snippet: String,
+}
+
+pub fn apply_suggestions(mode: ApplySuggestions,
+                         sopts: &config::Options,
+                         cfg: &ast::CrateConfig,
+                         input: &Input,
+                         input_path: &Option<PathBuf>,
+                         odir: &Option<PathBuf>,
+                         ofile: &Option<PathBuf>) {
+    if let Input::Str { .. } = *input {
+        early_warn(sopts.error_format,
+                   "`-Z apply-suggestions` can't change code read from the standard input");
+        return;
+    }
+
+    let passes = match mode {
+        ApplySuggestions::InPlace => MAX_PASSES,
+        ApplySuggestions::DryRun => 1,
+    };
+    for _ in 0..passes {
+        let changes = check(sopts, cfg, input, input_path, odir, ofile);
+        if changes.is_empty() {
+            return;
+        }
+        for (path, (old, new)) in changes {
+            match mode {
+                ApplySuggestions::InPlace => {
+                    if let Err(e) = write_fixed(&path, &new) {
+                        early_error(sopts.error_format,
+                                    &format!("couldn't apply suggestions to `{}`: {}",
+                                             path.display(), e));
+                    }
+                }
+                ApplySuggestions::DryRun => print!("{}", unified_diff(&path, &old, &new)),
+            }
+        }
+    }
+    if mode == ApplySuggestions::InPlace {
+        early_warn(sopts.error_format,
+                   &format!("`-Z apply-suggestions` stopped after {} passes, some suggestions may \
+                             not be applied", MAX_PASSES));
+    }
+}
+
+/// Writes the fixed contents of the file at `path`, along with its byte order mark if it had one.
+fn write_fixed(path: &Path, fixed: &str) -> io::Result<()> {
+    let mut contents = Vec::with_capacity(BOM.len() + fixed.len());
+    if fs::read(path)?.starts_with(BOM) {
+        contents.extend_from_slice(BOM);
+    }
+    contents.extend_from_slice(fixed.as_bytes());
+    fs::write(path, contents)
+}
+
+/// Checks the crate without reporting its diagnostics, and returns the current and the fixed
+/// contents of the files its suggestions change.
+fn check(sopts: &config::Options,
+         cfg: &ast::CrateConfig,
+         input: &Input,
+         input_path: &Option<PathBuf>,
+         odir: &Option<PathBuf>,
+         ofile: &Option<PathBuf>) -> BTreeMap<PathBuf, (String, String)> {
+    // The checks must not touch the incremental compilation cache of the actual compilation.
+    let mut sopts = sopts.clone();
+    sopts.incremental = None;
+
+    // The files are read again on each pass, so each pass needs its own source map.
+    let source_map = Lrc::new(SourceMap::new(sopts.file_path_mapping()));
+    let mut sess = session::build_session_with_source_map(
+        sopts, input_path.clone(), diagnostics_registry(), source_map, Some(Box::new(io::sink())),
+    );
+
+    let codegen_backend = get_codegen_backend(&sess);
+
+    rustc_lint::register_builtins(&mut sess.lint_store.borrow_mut(), Some(&sess));
+
+    let mut cfg = config::build_configuration(&sess, cfg.clone());
+    target_features::add_configuration(&mut cfg, &sess, &*codegen_backend);
+    sess.parse_sess.config = cfg;
+
+    let plugins = sess.opts.debugging_opts.extra_plugins.clone();
+    let cstore = CStore::new(codegen_backend.metadata_loader());
+    let mut control = CompileController::basic();
+    control.after_analysis.stop = Compilation::Stop;
+
+    // Errors are expected, some of them may be fixed by the suggestions.
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        driver::compile_input(codegen_backend,
+                              &sess,
+                              &cstore,
+                              input_path,
+                              input,
+                              odir,
+                              ofile,
+                              Some(plugins),
+                              &control)
+    }));
+    if let Err(payload) = result {
+        if !payload.is::<FatalErrorMarker>() {
+            panic::resume_unwind(payload);
+        }
+    }
+
+    let mut sources = BTreeMap::new();
+    let groups = sess.diagnostic()
+                     .take_suggestions()
+                     .iter()
+                     .filter_map(|suggestion| edits(sess.source_map(), suggestion, &mut sources))
+                     .collect();
+    resolve(groups).into_iter().map(|(path, edits)| {
+        let old = sources[&path].to_string();
+        let new = apply(&old, &edits);
+        (path, (old, new))
+    }).collect()
+}
+
+/// Returns the edits of `suggestion`, or `None` if it can't be applied, and adds the contents of
+/// the files they change to `sources`.
+fn edits(source_map: &SourceMap,
+         suggestion: &CodeSuggestion,
+         sources: &mut BTreeMap<PathBuf, Lrc<String>>) -> Option<Vec<(PathBuf, Edit)>> {
+    // Only apply suggestions that have a single way of fixing the code.
+    if suggestion.substitutions.len() != 1 {
+        return None;
+    }
+    suggestion.substitutions[0].parts.iter().map(|part| {
+        // Code written by macros can't be fixed where it is written.
+        if part.span.ctxt() != SyntaxContext::empty() {
+            return None;
+        }
+        let lo = source_map.lookup_byte_offset(part.span.lo());
+        let hi = source_map.lookup_byte_offset(part.span.hi());
+        if !Lrc::ptr_eq(&lo.sf, &hi.sf) {
+            return None;
+        }
+        let path = match (&lo.sf.name, &lo.sf.src) {
+            (&FileName::Real(ref path), &Some(ref src)) => {
+                sources.entry(path.clone()).or_insert_with(|| src.clone());
+                path.clone()
+            }
+            _ => return None,
+        };
+        Some((path, Edit {
+            lo: lo.pos.to_usize(),
+            hi: hi.pos.to_usize(),
+            snippet: part.snippet.clone(),
+        }))
+    }).collect()
+}
+
+fn overlaps(a: &Edit, b: &Edit) -> bool {
+    // Two insertions at the same place would be applied in an arbitrary order.
+    (a.lo < b.hi && b.lo < a.hi) || a.lo == b.lo
+}
+
+/// Chooses the suggestions to apply among `groups`, the edits of each suggestion, and returns
+/// their edits by file.
+///
+/// The suggestions are sorted by their edits, so that the result doesn't depend on the order
+/// the diagnostics were emitted in, and a suggestion is only chosen if none of its edits overlaps
+/// with the edits of the suggestions chosen before it.
+fn resolve(mut groups: Vec<Vec<(PathBuf, Edit)>>) -> BTreeMap<PathBuf, Vec<Edit>> {
+    groups.sort();
+    groups.dedup();
+
+    let mut chosen = BTreeMap::<PathBuf, Vec<Edit>>::new();
+    for group in groups {
+        let conflicts = group.iter().enumerate().any(|(i, &(ref path, ref edit))| {
+            let chosen_edits = chosen.get(path).into_iter().flat_map(|edits| edits.iter());
+            let earlier_edits = group[..i].iter()
+                .filter(|&&(ref p, _)| p == path)
+                .map(|&(_, ref e)| e);
+            chosen_edits.chain(earlier_edits).any(|e| overlaps(e, edit))
+        });
+        if conflicts {
+            continue;
+        }
+        for (path, edit) in group {
+            chosen.entry(path).or_default().push(edit);
+        }
+    }
+    for edits in chosen.values_mut() {
+        edits.sort();
+    }
+    chosen
+}
+
+/// Applies `edits`, sorted and not overlapping, to `src`.
+fn apply(src: &str, edits: &[Edit]) -> String {
+    let mut fixed = String::with_capacity(src.len());
+    let mut pos = 0;
+    for edit in edits {
+        fixed.push_str(&src[pos..edit.lo]);
+        fixed.push_str(&edit.snippet);
+        pos = edit.hi;
+    }
+    fixed.push_str(&src[pos..]);
+    fixed
+}
+
+/// Returns the lines of `old` and `new` prefixed with ` ` if they are in both, `-` if they are
+/// only in `old` or `+` if they are only in `new`.
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(char, &'a str)> {
+    let prefix = old.iter().zip(new).take_while(|&(a, b)| a == b).count();
+    let suffix = old[prefix..].iter().rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|&(a, b)| a == b)
+        .count();
+    let old_changed = &old[prefix..old.len() - suffix];
+    let new_changed = &new[prefix..new.len() - suffix];
+
+    // `common[i][j]` is the length of the longest common subsequence of `old_changed[i..]` and
+    // `new_changed[j..]`.
+    let mut common = vec![vec![0; new_changed.len() + 1]; old_changed.len() + 1];
+    for i in (0..old_changed.len()).rev() {
+        for j in (0..new_changed.len()).rev() {
+            common[i][j] = if old_changed[i] == new_changed[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                cmp::max(common[i + 1][j], common[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = old[..prefix].iter().map(|&line| (' ', line)).collect::<Vec<_>>();
+    let (mut i, mut j) = (0, 0);
+    while i < old_changed.len() || j < new_changed.len() {
+        if i < old_changed.len() && j < new_changed.len() && old_changed[i] == new_changed[j] {
+            lines.push((' ', old_changed[i]));
+            i += 1;
+            j += 1;
+        } else if j == new_changed.len() ||
+                  (i < old_changed.len() && common[i + 1][j] >= common[i][j + 1]) {
+            lines.push(('-', old_changed[i]));
+            i += 1;
+        } else {
+            lines.push(('+', new_changed[j]));
+            j += 1;
+        }
+    }
+    lines.extend(old[old.len() - suffix..].iter().map(|&line| (' ', line)));
+    lines
+}
+
+/// Returns the changes from `old` to `new`, the contents of `path`, as a unified diff.
+fn unified_diff(path: &Path, old: &str, new: &str) -> String {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+    let lines = diff_lines(&old, &new);
+    let changes = lines.iter()
+        .enumerate()
+        .filter(|&(_, &(kind, _))| kind != ' ')
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+
+    let mut diff = format!("--- {0}\n+++ {0}\n", path.display());
+    let mut next = 0;
+    while next < changes.len() {
+        // Changes separated by less than twice the context are in the same hunk.
+        let start = changes[next].saturating_sub(CONTEXT_LINES);
+        let mut end = changes[next] + 1;
+        next += 1;
+        while next < changes.len() && changes[next] <= end + 2 * CONTEXT_LINES {
+            end = changes[next] + 1;
+            next += 1;
+        }
+        let end = cmp::min(end + CONTEXT_LINES, lines.len());
+
+        let (before, hunk) = (&lines[..start], &lines[start..end]);
+        let range = |kind: char, part: &[(char, &str)]| {
+            part.iter().filter(|&&(k, _)| k != kind).count()
+        };
+        let (old_len, new_len) = (range('+', hunk), range('-', hunk));
+        // Empty ranges start at the line before them.
+        let old_start = range('+', before) + if old_len > 0 { 1 } else { 0 };
+        let new_start = range('-', before) + if new_len > 0 { 1 } else { 0 };
+        diff.push_str(&format!("@@ -{},{} +{},{} @@\n", old_start, old_len, new_start, new_len));
+        for &(kind, line) in hunk {
+            diff.push(kind);
+            diff.push_str(line);
+            diff.push('\n');
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::{apply, resolve, unified_diff, Edit};
+
+    fn edit(lo: usize, hi: usize, snippet: &str) -> (PathBuf, Edit) {
+        (PathBuf::from("lib.rs"), Edit { lo, hi, snippet: snippet.to_string() })
+    }
+
+    #[test]
+    fn test_apply() {
+        let edits = resolve(vec![vec![edit(8, 9, "_x")], vec![edit(4, 8, "")]]);
+        assert_eq!(apply("let mut x = 1;", &edits[Path::new("lib.rs")]), "let _x = 1;");
+    }
+
+    #[test]
+    fn test_resolve_overlaps() {
+        // The first suggestion wins whatever the order they were emitted in.
+        for groups in &[vec![vec![edit(0, 5, "a")], vec![edit(3, 8, "b")]],
+                        vec![vec![edit(3, 8, "b")], vec![edit(0, 5, "a")]]] {
+            let edits = resolve(groups.clone());
+            assert_eq!(edits[Path::new("lib.rs")], [edit(0, 5, "a").1]);
+        }
+
+        // A suggestion is applied with all of its edits or not at all.
+        let edits = resolve(vec![vec![edit(0, 1, "a"), edit(10, 11, "b")],
+                                 vec![edit(5, 6, "c"), edit(10, 10, "d")]]);
+        assert_eq!(edits[Path::new("lib.rs")], [edit(0, 1, "a").1, edit(10, 11, "b").1]);
+
+        // Two insertions at the same place conflict, while an identical suggestion is only
+        // applied once.
+        let edits = resolve(vec![vec![edit(3, 3, "a")], vec![edit(3, 3, "b")],
+                                 vec![edit(3, 3, "a")]]);
+        assert_eq!(edits[Path::new("lib.rs")], [edit(3, 3, "a").1]);
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\n";
+        let new = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\nn\n";
+        assert_eq!(unified_diff(Path::new("lib.rs"), old, new), "\
+--- lib.rs
++++ lib.rs
+@@ -1,5 +1,5 @@
+ a
+-b
++B
+ c
+ d
+ e
+@@ -11,3 +11,4 @@
+ k
+ l
+ m
++n
+");
+    }
+}
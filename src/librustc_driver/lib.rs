@@ -126,6 +126,7 @@ mod test;
 pub mod profile;
 pub mod driver;
 pub mod pretty;
+mod fix;
 mod proc_macro_decls;
 
 pub mod target_features {
@@ -510,11 +511,16 @@ fn run_compiler_with_pool<'a>(
         },
     };
 
+    if let Some(mode) = sopts.debugging_opts.apply_suggestions {
+        fix::apply_suggestions(mode, &sopts, &cfg, &input, &input_file_path, &odir, &ofile);
+    }
+
     let loader = file_loader.unwrap_or(box RealFileLoader);
     let source_map = Lrc::new(SourceMap::with_file_loader(loader, sopts.file_path_mapping()));
     let mut sess = session::build_session_with_source_map(
         sopts, input_file_path.clone(), descriptions, source_map, emitter_dest,
     );
+    session::emit_early_sarif_warnings(sess.diagnostic());
 
     if let Some(err) = input_err {
         // Immediately stop compilation if there was an issue reading
@@ -544,7 +550,10 @@ fn run_compiler_with_pool<'a>(
                                               &odir,
                                               &ofile), Some(sess));
 
-        let _sess_abort_error = OnDrop(|| sess.diagnostic().print_error_count());
+        let _sess_abort_error = OnDrop(|| {
+            sess.diagnostic().write_baseline();
+            sess.diagnostic().print_error_count();
+        });
 
         let control = callbacks.build_controller(&sess, &matches);
 
@@ -840,6 +849,7 @@ impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
                 let mut sess = build_session(sopts.clone(),
                     None,
                     descriptions.clone());
+                session::emit_early_sarif_warnings(sess.diagnostic());
                 if sopts.describe_lints {
                     let mut ls = lint::LintStore::new();
                     rustc_lint::register_builtins(&mut ls, Some(&sess));
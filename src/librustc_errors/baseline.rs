@@ -0,0 +1,195 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Diagnostic baselines, the lints of a crate recorded so that later builds only report the lints
+//! of new code.
+//!
+//! Each lint is recorded as its name, the file it was reported in, and a fingerprint of its message
+//! and of the code of its primary span. Unlike the line of the lint, none of them changes when
+//! the code around it does. Files are written relative to the working directory, with `/` as
+//! their separator, so that a baseline can be shared between machines. A baseline counts the
+//! lints it has: a lint reported twice on the same code of a file is recorded twice, and only
+//! hidden twice.
+//!
+//! Other diagnostics are never recorded, and errors that are not lints can't be hidden.
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::hash::Hash;
+use std::io;
+use std::path::{self, Path, PathBuf};
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::stable_hasher::StableHasher;
+use rustc_data_structures::sync::{Lock, Lrc};
+use syntax_pos::{FileName, Pos, Span};
+
+use {Diagnostic, DiagnosticId, SourceMapperDyn};
+
+const HEADER: &str = "# rustc diagnostic baseline: lint name, file and fingerprint";
+
+/// A lint, as recorded in a baseline.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+struct Entry {
+    lint: String,
+    file: String,
+    fingerprint: u64,
+}
+
+impl Entry {
+    fn parse(line: &str) -> Option<Entry> {
+        let mut fields = line.split('\t');
+        match (fields.next(), fields.next(), fields.next(), fields.next()) {
+            (Some(lint), Some(file), Some(fingerprint), None) => Some(Entry {
+                lint: lint.to_string(),
+                file: file.to_string(),
+                fingerprint: u64::from_str_radix(fingerprint, 16).ok()?,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}\t{}\t{:016x}", self.lint, self.file, self.fingerprint)
+    }
+}
+
+enum Mode {
+    /// The lints to hide, with the number of times each of them can still be hidden.
+    Hide(Lock<FxHashMap<Entry, usize>>),
+    /// The file to write the baseline to, and the lints reported so far.
+    Record(PathBuf, Lock<Vec<Entry>>),
+}
+
+pub struct Baseline {
+    sm: Lrc<SourceMapperDyn>,
+    /// The directory the files of the entries are relative to.
+    working_dir: PathBuf,
+    mode: Mode,
+}
+
+impl Baseline {
+    /// Reads the baseline at `path`, whose lints will be hidden.
+    pub fn load(path: &Path, sm: Lrc<SourceMapperDyn>) -> io::Result<Baseline> {
+        let mut entries = FxHashMap::default();
+        for line in fs::read_to_string(path)?.lines() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let entry = Entry::parse(line).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("invalid entry `{}`", line))
+            })?;
+            *entries.entry(entry).or_insert(0) += 1;
+        }
+        Ok(Baseline {
+            sm,
+            working_dir: env::current_dir().unwrap_or_default(),
+            mode: Mode::Hide(Lock::new(entries)),
+        })
+    }
+
+    /// Creates a baseline that records the lints reported, to be written to `path`.
+    pub fn record(path: PathBuf, sm: Lrc<SourceMapperDyn>) -> Baseline {
+        Baseline {
+            sm,
+            working_dir: env::current_dir().unwrap_or_default(),
+            mode: Mode::Record(path, Lock::new(Vec::new())),
+        }
+    }
+
+    /// Returns whether `diagnostic` is in the baseline and must not be reported. When recording,
+    /// adds `diagnostic` to the baseline if it is a lint, and always returns false.
+    pub fn hides(&self, diagnostic: &Diagnostic) -> bool {
+        let entry = match self.entry(diagnostic) {
+            Some(entry) => entry,
+            None => return false,
+        };
+        match self.mode {
+            Mode::Hide(ref entries) => match entries.borrow_mut().get_mut(&entry) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    true
+                }
+                _ => false,
+            },
+            Mode::Record(_, ref entries) => {
+                entries.borrow_mut().push(entry);
+                false
+            }
+        }
+    }
+
+    /// Writes the lints recorded so far to the baseline file, if the baseline is being recorded.
+    /// Returns the path of the file on failure.
+    pub fn write(&self) -> Result<(), (PathBuf, io::Error)> {
+        let (path, entries) = match self.mode {
+            Mode::Hide(_) => return Ok(()),
+            Mode::Record(ref path, ref entries) => (path, entries),
+        };
+        // Sorted, so that the baseline can be kept in version control.
+        let mut entries = entries.borrow().clone();
+        entries.sort();
+        let mut contents = format!("{}\n", HEADER);
+        for entry in entries {
+            contents.push_str(&format!("{}\n", entry));
+        }
+        fs::write(path, contents).map_err(|e| (path.clone(), e))
+    }
+
+    fn entry(&self, diagnostic: &Diagnostic) -> Option<Entry> {
+        let lint = match diagnostic.code {
+            Some(DiagnosticId::Lint(ref lint)) => lint.clone(),
+            _ => return None,
+        };
+        let (file, code) = match diagnostic.span.primary_span() {
+            Some(span) if !span.is_dummy() => (self.file(span), self.snippet(span)),
+            _ => (String::new(), String::new()),
+        };
+
+        let mut hasher = StableHasher::<u64>::new();
+        diagnostic.message().hash(&mut hasher);
+        // Reindenting the code doesn't change the fingerprint.
+        for word in code.split_whitespace() {
+            word.hash(&mut hasher);
+        }
+        Some(Entry {
+            lint,
+            file,
+            fingerprint: hasher.finish(),
+        })
+    }
+
+    /// Returns the file of `span`, relative to the working directory and with `/` as its separator.
+    fn file(&self, span: Span) -> String {
+        match self.sm.span_to_filename(span) {
+            FileName::Real(path) => {
+                let path = path.strip_prefix(&self.working_dir).unwrap_or(&path);
+                path.to_string_lossy().replace(path::MAIN_SEPARATOR, "/")
+            }
+            name => name.to_string(),
+        }
+    }
+
+    /// Returns the code of `span`, or an empty string if it isn't available.
+    fn snippet(&self, span: Span) -> String {
+        let file = self.sm.lookup_char_pos(span.lo()).file;
+        match file.src {
+            Some(ref src) if span.hi() <= file.end_pos => {
+                let lo = (span.lo() - file.start_pos).to_usize();
+                let hi = (span.hi() - file.start_pos).to_usize();
+                src.get(lo..hi).unwrap_or("").to_string()
+            }
+            _ => String::new(),
+        }
+    }
+}
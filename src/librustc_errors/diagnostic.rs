@@ -13,6 +13,8 @@ use SubstitutionPart;
 use Substitution;
 use Applicability;
 use Level;
+use LocalizedMessage;
+use LocalizedPart;
 use std::fmt;
 use syntax_pos::{MultiSpan, Span};
 use snippet::Style;
@@ -26,6 +28,8 @@ pub struct Diagnostic {
     pub span: MultiSpan,
     pub children: Vec<SubDiagnostic>,
     pub suggestions: Vec<CodeSuggestion>,
+    /// The parts of the diagnostic that can be translated, along with their message.
+    pub localized: Vec<(LocalizedPart, LocalizedMessage)>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
@@ -96,6 +100,7 @@ impl Diagnostic {
             span: MultiSpan::new(),
             children: vec![],
             suggestions: vec![],
+            localized: vec![],
         }
     }
 
@@ -139,6 +144,13 @@ impl Diagnostic {
         self
     }
 
+    /// Like `span_label`, but the label can be translated.
+    pub fn localized_span_label(&mut self, span: Span, msg: LocalizedMessage) -> &mut Self {
+        self.span.push_span_label(span, msg.to_english());
+        self.localized.push((LocalizedPart::Label(span), msg));
+        self
+    }
+
     pub fn replace_span_with(&mut self, after: Span) -> &mut Self {
         let before = self.span.clone();
         self.set_span(after);
@@ -213,6 +225,19 @@ impl Diagnostic {
         self
     }
 
+    pub fn localized_note(&mut self, msg: LocalizedMessage) -> &mut Self {
+        self.localized_sub(Level::Note, msg, MultiSpan::new());
+        self
+    }
+
+    pub fn localized_span_note<S: Into<MultiSpan>>(&mut self,
+                                                   sp: S,
+                                                   msg: LocalizedMessage)
+                                                   -> &mut Self {
+        self.localized_sub(Level::Note, msg, sp.into());
+        self
+    }
+
     pub fn warn(&mut self, msg: &str) -> &mut Self {
         self.sub(Level::Warning, msg, MultiSpan::new(), None);
         self
@@ -239,6 +264,19 @@ impl Diagnostic {
         self
     }
 
+    pub fn localized_help(&mut self, msg: LocalizedMessage) -> &mut Self {
+        self.localized_sub(Level::Help, msg, MultiSpan::new());
+        self
+    }
+
+    pub fn localized_span_help<S: Into<MultiSpan>>(&mut self,
+                                                   sp: S,
+                                                   msg: LocalizedMessage)
+                                                   -> &mut Self {
+        self.localized_sub(Level::Help, msg, sp.into());
+        self
+    }
+
     /// Prints out a message with a suggested edit of the code. If the suggestion is presented
     /// inline it will only show the text message and not the text.
     ///
@@ -416,12 +454,29 @@ impl Diagnostic {
         &self.message
     }
 
+    /// Like `message`, but the message can be translated.
+    pub fn localized_message(&mut self, msg: LocalizedMessage) -> &mut Self {
+        self.message = vec![(msg.to_english(), Style::NoStyle)];
+        self.localized.retain(|&(ref part, _)| *part != LocalizedPart::Message);
+        self.localized.push((LocalizedPart::Message, msg));
+        self
+    }
+
     /// Used by a lint. Copies over all details *but* the "main
     /// message".
     pub fn copy_details_not_message(&mut self, from: &Diagnostic) {
         self.span = from.span.clone();
         self.code = from.code.clone();
-        self.children.extend(from.children.iter().cloned())
+        let offset = self.children.len();
+        self.children.extend(from.children.iter().cloned());
+        self.localized.extend(from.localized.iter().filter_map(|&(ref part, ref msg)| {
+            let part = match *part {
+                LocalizedPart::Message => return None,
+                LocalizedPart::Child(i) => LocalizedPart::Child(offset + i),
+                LocalizedPart::Label(span) => LocalizedPart::Label(span),
+            };
+            Some((part, msg.clone()))
+        }));
     }
 
     /// Convenience function for internal use, clients should use one of the
@@ -440,6 +495,13 @@ impl Diagnostic {
         self.children.push(sub);
     }
 
+    /// Convenience function for internal use, clients should use one of the
+    /// public methods above.
+    fn localized_sub(&mut self, level: Level, msg: LocalizedMessage, span: MultiSpan) {
+        self.sub(level, &msg.to_english(), span, None);
+        self.localized.push((LocalizedPart::Child(self.children.len() - 1), msg));
+    }
+
     /// Convenience function for internal use, clients should use one of the
     /// public methods above.
     fn sub_with_highlights(&mut self,
@@ -12,6 +12,7 @@ use Diagnostic;
 use DiagnosticId;
 use DiagnosticStyledString;
 use Applicability;
+use LocalizedMessage;
 
 use Level;
 use Handler;
@@ -187,6 +188,22 @@ impl<'a> DiagnosticBuilder<'a> {
                                                   msg: &str,
                                                   ) -> &mut Self);
 
+    forward!(pub fn localized_message(&mut self, msg: LocalizedMessage) -> &mut Self);
+    forward!(pub fn localized_span_label(&mut self,
+                                         span: Span,
+                                         msg: LocalizedMessage,
+                                         ) -> &mut Self);
+    forward!(pub fn localized_note(&mut self, msg: LocalizedMessage) -> &mut Self);
+    forward!(pub fn localized_span_note<S: Into<MultiSpan>>(&mut self,
+                                                            sp: S,
+                                                            msg: LocalizedMessage,
+                                                            ) -> &mut Self);
+    forward!(pub fn localized_help(&mut self, msg: LocalizedMessage) -> &mut Self);
+    forward!(pub fn localized_span_help<S: Into<MultiSpan>>(&mut self,
+                                                            sp: S,
+                                                            msg: LocalizedMessage,
+                                                            ) -> &mut Self);
+
     #[deprecated(note = "Use `span_suggestion_short_with_applicability`")]
     forward!(pub fn span_suggestion_short(
                                       &mut self,
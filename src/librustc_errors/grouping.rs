@@ -0,0 +1,105 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Grouping of errors by root cause, with `-Z group-diagnostics`.
+//!
+//! One mistake, like a wrong type, often causes many errors. With `-Z group-diagnostics`, the
+//! `Handler` keeps the errors until the end of the compilation, or until the compilation is
+//! aborted, and reports them grouped: two errors are related if they have the same primary span,
+//! or if they have the same error code and were emitted while checking the same item, their
+//! origin. Related errors end up in the same group, whose first error, the one emitted first, is
+//! taken as the root cause of the others.
+
+use std::cell::Cell;
+use std::cmp;
+
+use rustc_data_structures::fx::FxHashMap;
+
+use Diagnostic;
+
+thread_local!(static ORIGIN: Cell<Option<u64>> = Cell::new(None));
+
+/// Makes `origin` the origin of the errors emitted until the guard is dropped, which restores the
+/// previous origin.
+///
+/// Origins are opaque to the `Handler`: the compiler uses the hash of the path of the item being
+/// checked.
+pub fn set_diagnostic_origin(origin: u64) -> DiagnosticOriginGuard {
+    DiagnosticOriginGuard {
+        previous: ORIGIN.with(|current| current.replace(Some(origin))),
+    }
+}
+
+pub struct DiagnosticOriginGuard {
+    previous: Option<u64>,
+}
+
+impl Drop for DiagnosticOriginGuard {
+    fn drop(&mut self) {
+        ORIGIN.with(|current| current.set(self.previous));
+    }
+}
+
+/// Returns the origin of the errors being emitted.
+pub fn current_origin() -> Option<u64> {
+    ORIGIN.with(|current| current.get())
+}
+
+/// Splits `diagnostics`, in the order they were emitted along with their origin, into groups of
+/// related diagnostics. The groups are in the order of their first diagnostic, which is their
+/// root cause, and the diagnostics of each group are in the order they were emitted.
+pub fn group(diagnostics: Vec<(Diagnostic, Option<u64>)>) -> Vec<Vec<Diagnostic>> {
+    // A union-find over the indices of the diagnostics, whose representatives are the smallest
+    // index of their set, so that the root cause of a group is its representative.
+    let mut parents = (0..diagnostics.len()).collect::<Vec<_>>();
+    fn find(parents: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while parents[root] != root {
+            root = parents[root];
+        }
+        let mut i = i;
+        while parents[i] != root {
+            let next = parents[i];
+            parents[i] = root;
+            i = next;
+        }
+        root
+    }
+
+    let mut by_span = FxHashMap::default();
+    let mut by_origin = FxHashMap::default();
+    for (i, &(ref diagnostic, origin)) in diagnostics.iter().enumerate() {
+        let mut related = Vec::new();
+        if let Some(span) = diagnostic.span.primary_span() {
+            if !span.is_dummy() {
+                related.push(*by_span.entry(span).or_insert(i));
+            }
+        }
+        if let Some(origin) = origin {
+            related.push(*by_origin.entry((origin, diagnostic.code.clone())).or_insert(i));
+        }
+        for j in related {
+            let (a, b) = (find(&mut parents, i), find(&mut parents, j));
+            parents[cmp::max(a, b)] = cmp::min(a, b);
+        }
+    }
+
+    let mut groups = Vec::<Vec<Diagnostic>>::new();
+    let mut group_of_root = FxHashMap::default();
+    for (i, (diagnostic, _)) in diagnostics.into_iter().enumerate() {
+        let root = find(&mut parents, i);
+        let index = *group_of_root.entry(root).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[index].push(diagnostic);
+    }
+    groups
+}
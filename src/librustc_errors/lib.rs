@@ -31,6 +31,9 @@ extern crate syntax_pos;
 extern crate unicode_width;
 
 pub use emitter::ColorConfig;
+pub use baseline::Baseline;
+pub use grouping::{set_diagnostic_origin, DiagnosticOriginGuard};
+pub use translation::{LocalizedMessage, LocalizedPart, TranslationBundle};
 
 use self::Level::*;
 
@@ -42,6 +45,7 @@ use rustc_data_structures::stable_hasher::StableHasher;
 
 use std::borrow::Cow;
 use std::cell::Cell;
+use std::mem;
 use std::{error, fmt};
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering::SeqCst;
@@ -49,13 +53,16 @@ use std::panic;
 
 use termcolor::{ColorSpec, Color};
 
+mod baseline;
 mod diagnostic;
 mod diagnostic_builder;
+mod grouping;
 pub mod emitter;
 mod snippet;
 pub mod registry;
 mod styled_buffer;
 mod lock;
+mod translation;
 
 use syntax_pos::{BytePos,
                  Loc,
@@ -296,6 +303,20 @@ pub struct Handler {
     // this handler. These hashes is used to avoid emitting the same error
     // twice.
     emitted_diagnostics: Lock<FxHashSet<u128>>,
+
+    /// The machine-applicable suggestions of the emitted diagnostics, if
+    /// `flags.collect_suggestions` is set.
+    suggestions: Lock<Vec<CodeSuggestion>>,
+
+    /// The lints that are not reported, or that are being recorded.
+    baseline: Option<Baseline>,
+
+    /// The errors kept to be reported grouped, if `flags.group_diagnostics` is set, along with
+    /// their origin.
+    grouped_diagnostics: Lock<Vec<(Diagnostic, Option<u64>)>>,
+
+    /// The translations of the localized messages of the diagnostics.
+    translation: Option<TranslationBundle>,
 }
 
 fn default_track_diagnostic(_: &Diagnostic) {}
@@ -320,10 +341,21 @@ pub struct HandlerFlags {
     /// show macro backtraces even for non-local macros.
     /// (rustc: see `-Z external-macro-backtrace`)
     pub external_macro_backtrace: bool,
+    /// If true, keep the machine-applicable suggestions of the emitted diagnostics.
+    /// (rustc: see `-Z apply-suggestions`)
+    pub collect_suggestions: bool,
+    /// If true, keep the errors until they must be reported, and report them grouped by root
+    /// cause, hiding all but the root causes unless `expand_diagnostic_groups` is set.
+    /// (rustc: see `-Z group-diagnostics`)
+    pub group_diagnostics: bool,
+    /// If true, report all the errors of a group after their root cause.
+    /// (rustc: see `-Z expand-diagnostic-groups`)
+    pub expand_diagnostic_groups: bool,
 }
 
 impl Drop for Handler {
     fn drop(&mut self) {
+        self.emit_grouped_diagnostics();
         if self.err_count() == 0 {
             let mut bugs = self.delayed_span_bugs.borrow_mut();
             let has_bugs = !bugs.is_empty();
@@ -385,9 +417,34 @@ impl Handler {
             taught_diagnostics: Default::default(),
             emitted_diagnostic_codes: Default::default(),
             emitted_diagnostics: Default::default(),
+            suggestions: Default::default(),
+            baseline: None,
+            grouped_diagnostics: Default::default(),
+            translation: None,
+        }
+    }
+
+    /// Hides the lints of `baseline` from now on, or records them if the baseline is being
+    /// recorded.
+    /// (rustc: see `-Z diagnostic-baseline`)
+    pub fn set_baseline(&mut self, baseline: Baseline) {
+        self.baseline = Some(baseline);
+    }
+
+    /// Writes the lints recorded to the baseline file, if the baseline is being recorded.
+    pub fn write_baseline(&self) {
+        if let Some(Err((path, e))) = self.baseline.as_ref().map(|baseline| baseline.write()) {
+            self.err(&format!("couldn't write the diagnostic baseline `{}`: {}",
+                              path.display(), e));
         }
     }
 
+    /// Translates the localized messages of the diagnostics emitted from now on.
+    /// (rustc: see `-Z translation-bundle`)
+    pub fn set_translation_bundle(&mut self, bundle: TranslationBundle) {
+        self.translation = Some(bundle);
+    }
+
     pub fn set_continue_after_error(&self, continue_after_error: bool) {
         self.continue_after_error.set(continue_after_error);
     }
@@ -456,6 +513,18 @@ impl Handler {
         result.code(code);
         result
     }
+    /// Like `struct_span_err_with_code`, but the message can be translated.
+    pub fn struct_span_err_localized<'a, S: Into<MultiSpan>>(&'a self,
+                                                             sp: S,
+                                                             msg: LocalizedMessage,
+                                                             code: DiagnosticId)
+                                                             -> DiagnosticBuilder<'a> {
+        let mut result = DiagnosticBuilder::new(self, Level::Error, "");
+        result.localized_message(msg);
+        result.set_span(sp);
+        result.code(code);
+        result
+    }
     // FIXME: This method should be removed (every error should have an associated error code).
     pub fn struct_err<'a>(&'a self, msg: &str) -> DiagnosticBuilder<'a> {
         DiagnosticBuilder::new(self, Level::Error, msg)
@@ -616,7 +685,15 @@ impl Handler {
         self.err_count() > 0
     }
 
+    /// Takes the machine-applicable suggestions emitted so far. They are only kept if
+    /// `flags.collect_suggestions` is set.
+    pub fn take_suggestions(&self) -> Vec<CodeSuggestion> {
+        mem::replace(&mut *self.suggestions.borrow_mut(), Vec::new())
+    }
+
     pub fn print_error_count(&self) {
+        self.emit_grouped_diagnostics();
+
         let s = match self.err_count() {
             0 => return,
             1 => "aborting due to previous error".to_string(),
@@ -659,6 +736,7 @@ impl Handler {
         if self.err_count() == 0 {
             return;
         }
+        self.emit_grouped_diagnostics();
         FatalError.raise();
     }
     pub fn emit(&self, msp: &MultiSpan, msg: &str, lvl: Level) {
@@ -694,10 +772,53 @@ impl Handler {
     }
 
     pub fn force_print_db(&self, mut db: DiagnosticBuilder) {
-        self.emitter.borrow_mut().emit(&db);
+        self.emit_translated(&db);
         db.cancel();
     }
 
+    /// Emits `db`, with its localized messages translated if there is a translation bundle.
+    fn emit_translated(&self, db: &DiagnosticBuilder) {
+        match self.translation.as_ref().and_then(|bundle| bundle.translate(db)) {
+            Some(translated) => {
+                let mut translated = DiagnosticBuilder::new_diagnostic(self, translated);
+                self.emitter.borrow_mut().emit(&translated);
+                translated.cancel();
+            }
+            None => self.emitter.borrow_mut().emit(db),
+        }
+    }
+
+    /// Reports the errors kept by `-Z group-diagnostics`, each group starting with its root
+    /// cause.
+    fn emit_grouped_diagnostics(&self) {
+        let diagnostics = mem::replace(&mut *self.grouped_diagnostics.borrow_mut(), Vec::new());
+        for group in grouping::group(diagnostics) {
+            let mut group = group.into_iter();
+            let mut root = group.next().unwrap();
+            let related = group.collect::<Vec<_>>();
+            let (count, s) = (related.len(), if related.len() == 1 { "" } else { "s" });
+            let root_message = root.message();
+
+            if self.flags.expand_diagnostic_groups {
+                if count > 0 {
+                    let verb = if count == 1 { "follows" } else { "follow" };
+                    root.note(&format!("{} related error{} {}", count, s, verb));
+                }
+                self.force_print_db(DiagnosticBuilder::new_diagnostic(self, root));
+                for mut diagnostic in related {
+                    diagnostic.note(&format!("related to the error: {}", root_message));
+                    self.force_print_db(DiagnosticBuilder::new_diagnostic(self, diagnostic));
+                }
+            } else {
+                if count > 0 {
+                    root.note(&format!("and {} related error{}, shown with \
+                                        `-Z expand-diagnostic-groups`", count, s));
+                }
+                self.force_print_db(DiagnosticBuilder::new_diagnostic(self, root));
+            }
+        }
+    }
+
     fn emit_db(&self, db: &DiagnosticBuilder) {
         let diagnostic = &**db;
 
@@ -719,7 +840,30 @@ impl Handler {
         // Only emit the diagnostic if we haven't already emitted an equivalent
         // one:
         if self.emitted_diagnostics.borrow_mut().insert(diagnostic_hash) {
-            self.emitter.borrow_mut().emit(db);
+            if self.baseline.as_ref().map_or(false, |baseline| baseline.hides(diagnostic)) {
+                return;
+            }
+            if self.flags.collect_suggestions {
+                let suggestions = db.suggestions.iter().filter(|suggestion| {
+                    suggestion.applicability == Applicability::MachineApplicable
+                });
+                self.suggestions.borrow_mut().extend(suggestions.cloned());
+            }
+
+            // With `-Z treat-err-as-bug`, the error must be reported before panicking.
+            let group = self.flags.group_diagnostics && !self.flags.treat_err_as_bug;
+            if group && db.level == Level::Error {
+                let origin = grouping::current_origin();
+                self.grouped_diagnostics.borrow_mut().push((diagnostic.clone(), origin));
+                self.bump_err_count();
+                return;
+            }
+            if db.is_error() {
+                // The errors that lead to a fatal error are reported before it.
+                self.emit_grouped_diagnostics();
+            }
+
+            self.emit_translated(db);
             if db.is_error() {
                 self.bump_err_count();
             }
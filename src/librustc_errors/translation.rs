@@ -0,0 +1,180 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Localizable diagnostic messages, translated with `-Z translation-bundle`.
+//!
+//! A localized message is identified by an id, like `typeck-field-already-declared`, and is
+//! written as an English template whose `{name}` placeholders are replaced by named arguments:
+//!
+//! ```ignore (illustrative)
+//! let msg = LocalizedMessage::new("typeck-field-already-declared",
+//!                                 "field `{field}` is already declared")
+//!     .arg("field", field_name);
+//! struct_span_err_localized!(tcx.sess, span, E0124, msg).emit();
+//! ```
+//!
+//! The English template is the built-in translation, and is what the diagnostic says until it is
+//! emitted. When the `Handler` has a `TranslationBundle`, the localized parts of a diagnostic,
+//! its message, labels and children, are replaced by their translation at emit time. The parts
+//! that the bundle has no translation for, and the messages that are not localized yet, stay in
+//! English, so that diagnostics can be migrated one at a time.
+//!
+//! Bundles are text files with one `id = template` line per message. Blank lines and lines
+//! starting with `#` are ignored, and `{{` and `}}` are a literal `{` and `}`.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use rustc_data_structures::fx::FxHashMap;
+
+use syntax_pos::Span;
+
+use Diagnostic;
+use snippet::Style;
+
+/// A message identified by an id, along with its English template and the values of its
+/// arguments.
+#[derive(Clone, Debug, PartialEq, Hash, RustcEncodable, RustcDecodable)]
+pub struct LocalizedMessage {
+    pub id: String,
+    pub fallback: String,
+    pub args: Vec<(String, String)>,
+}
+
+impl LocalizedMessage {
+    pub fn new(id: &str, fallback: &str) -> LocalizedMessage {
+        LocalizedMessage {
+            id: id.to_string(),
+            fallback: fallback.to_string(),
+            args: vec![],
+        }
+    }
+
+    /// Sets the argument `name`, which replaces the `{name}` placeholders of the message.
+    pub fn arg<T: fmt::Display>(mut self, name: &str, value: T) -> LocalizedMessage {
+        self.args.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Returns the message in English.
+    pub fn to_english(&self) -> String {
+        format_message(&self.fallback, &self.args)
+    }
+}
+
+/// The part of a diagnostic a localized message is.
+#[derive(Clone, Debug, PartialEq, Hash, RustcEncodable, RustcDecodable)]
+pub enum LocalizedPart {
+    /// The message of the diagnostic.
+    Message,
+    /// The message of the child at this index.
+    Child(usize),
+    /// A label of this span.
+    Label(Span),
+}
+
+/// The translations of localized messages, by id.
+#[derive(Default)]
+pub struct TranslationBundle {
+    messages: FxHashMap<String, String>,
+}
+
+impl TranslationBundle {
+    pub fn new() -> TranslationBundle {
+        TranslationBundle::default()
+    }
+
+    /// Adds the translations of the bundle at `path`, which override the ones loaded before.
+    pub fn load(&mut self, path: &Path) -> io::Result<()> {
+        for line in fs::read_to_string(path)?.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (id, template) = match line.find('=') {
+                Some(pos) if !line[..pos].trim().is_empty() => {
+                    (line[..pos].trim(), line[pos + 1..].trim())
+                }
+                _ => {
+                    let msg = format!("invalid line `{}`", line);
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                }
+            };
+            self.messages.insert(id.to_string(), template.to_string());
+        }
+        Ok(())
+    }
+
+    /// Returns `diagnostic` with its localized parts translated, or `None` if the bundle has no
+    /// translation for any of them.
+    pub fn translate(&self, diagnostic: &Diagnostic) -> Option<Diagnostic> {
+        let mut translated = None;
+        for &(ref part, ref msg) in &diagnostic.localized {
+            let template = match self.messages.get(&msg.id) {
+                Some(template) => template,
+                None => continue,
+            };
+            let text = format_message(template, &msg.args);
+            let diagnostic = translated.get_or_insert_with(|| diagnostic.clone());
+            match *part {
+                LocalizedPart::Message => {
+                    diagnostic.message = vec![(text, Style::NoStyle)];
+                }
+                LocalizedPart::Child(i) => {
+                    if let Some(child) = diagnostic.children.get_mut(i) {
+                        child.message = vec![(text, Style::NoStyle)];
+                    }
+                }
+                LocalizedPart::Label(span) => {
+                    diagnostic.span.replace_span_label(span, &msg.to_english(), text);
+                }
+            }
+        }
+        translated
+    }
+}
+
+/// Replaces the `{name}` placeholders of `template` with the value of the argument `name`.
+/// Placeholders without an argument are kept as they are.
+fn format_message(template: &str, args: &[(String, String)]) -> String {
+    let mut formatted = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(pos) = rest.find(|c: char| c == '{' || c == '}') {
+        formatted.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            formatted.push_str(&rest[..1]);
+            rest = &rest[2..];
+            continue;
+        }
+        let arg = if rest.starts_with('{') {
+            rest.find('}').and_then(|end| {
+                let name = &rest[1..end];
+                args.iter().find(|arg| arg.0 == name).map(|arg| (end, &arg.1))
+            })
+        } else {
+            None
+        };
+        match arg {
+            Some((end, value)) => {
+                formatted.push_str(value);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                formatted.push_str(&rest[..1]);
+                rest = &rest[1..];
+            }
+        }
+    }
+    formatted.push_str(rest);
+    formatted
+}
@@ -10,7 +10,7 @@
 
 use rustc::session::config::BorrowckMode;
 use rustc::ty::{self, TyCtxt};
-use rustc_errors::{DiagnosticBuilder, DiagnosticId};
+use rustc_errors::{DiagnosticBuilder, DiagnosticId, LocalizedMessage};
 use syntax_pos::{MultiSpan, Span};
 
 use std::fmt;
@@ -63,6 +63,13 @@ pub trait BorrowckErrors<'cx>: Sized + Copy {
         code: DiagnosticId,
     ) -> DiagnosticBuilder<'cx>;
 
+    fn struct_span_err_localized<S: Into<MultiSpan>>(
+        self,
+        sp: S,
+        msg: LocalizedMessage,
+        code: DiagnosticId,
+    ) -> DiagnosticBuilder<'cx>;
+
     fn struct_span_err<S: Into<MultiSpan>>(self, sp: S, msg: &str) -> DiagnosticBuilder<'cx>;
 
     /// Cancels the given error if we shouldn't emit errors for a given
@@ -101,20 +108,22 @@ pub trait BorrowckErrors<'cx>: Sized + Copy {
         borrow_desc: &str,
         o: Origin,
     ) -> DiagnosticBuilder<'cx> {
-        let mut err = struct_span_err!(
-            self,
-            span,
-            E0503,
-            "cannot use `{}` because it was mutably borrowed{OGN}",
-            desc,
-            OGN = o
-        );
+        let msg = LocalizedMessage::new(
+            "borrowck-cannot-use-when-mutably-borrowed",
+            "cannot use `{place}` because it was mutably borrowed{origin}",
+        ).arg("place", desc).arg("origin", o);
+        let mut err = struct_span_err_localized!(self, span, E0503, msg);
 
-        err.span_label(
+        err.localized_span_label(
             borrow_span,
-            format!("borrow of `{}` occurs here", borrow_desc),
+            LocalizedMessage::new("borrowck-borrow-occurs-here", "borrow of `{place}` occurs here")
+                .arg("place", borrow_desc),
+        );
+        err.localized_span_label(
+            span,
+            LocalizedMessage::new("borrowck-use-of-borrowed", "use of borrowed `{place}`")
+                .arg("place", borrow_desc),
         );
-        err.span_label(span, format!("use of borrowed `{}`", borrow_desc));
 
         self.cancel_if_wrong_origin(err, o)
     }
@@ -807,6 +816,15 @@ impl<'cx, 'gcx, 'tcx> BorrowckErrors<'cx> for TyCtxt<'cx, 'gcx, 'tcx> {
         self.sess.struct_span_err_with_code(sp, msg, code)
     }
 
+    fn struct_span_err_localized<S: Into<MultiSpan>>(
+        self,
+        sp: S,
+        msg: LocalizedMessage,
+        code: DiagnosticId,
+    ) -> DiagnosticBuilder<'cx> {
+        self.sess.struct_span_err_localized(sp, msg, code)
+    }
+
     fn struct_span_err<S: Into<MultiSpan>>(self, sp: S, msg: &str) -> DiagnosticBuilder<'cx> {
         self.sess.struct_span_err(sp, msg)
     }
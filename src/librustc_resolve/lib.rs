@@ -72,7 +72,7 @@ use syntax::ast::{QSelf, TraitItemKind, TraitRef, Ty, TyKind};
 use syntax::ptr::P;
 
 use syntax_pos::{Span, DUMMY_SP, MultiSpan};
-use errors::{Applicability, DiagnosticBuilder, DiagnosticId};
+use errors::{Applicability, DiagnosticBuilder, DiagnosticId, LocalizedMessage};
 
 use std::cell::{Cell, RefCell};
 use std::{cmp, fmt, iter, ptr};
@@ -331,21 +331,27 @@ fn resolve_struct_error<'sess, 'a>(resolver: &'sess Resolver,
             err
         }
         ResolutionError::IdentifierBoundMoreThanOnceInParameterList(identifier) => {
-            let mut err = struct_span_err!(resolver.session,
-                             span,
-                             E0415,
-                             "identifier `{}` is bound more than once in this parameter list",
-                             identifier);
-            err.span_label(span, "used as parameter more than once");
+            let msg = LocalizedMessage::new(
+                "resolve-identifier-bound-more-than-once-in-parameter-list",
+                "identifier `{identifier}` is bound more than once in this parameter list",
+            ).arg("identifier", identifier);
+            let mut err = struct_span_err_localized!(resolver.session, span, E0415, msg);
+            err.localized_span_label(span, LocalizedMessage::new(
+                "resolve-used-as-parameter-more-than-once",
+                "used as parameter more than once",
+            ));
             err
         }
         ResolutionError::IdentifierBoundMoreThanOnceInSamePattern(identifier) => {
-            let mut err = struct_span_err!(resolver.session,
-                             span,
-                             E0416,
-                             "identifier `{}` is bound more than once in the same pattern",
-                             identifier);
-            err.span_label(span, "used in a pattern more than once");
+            let msg = LocalizedMessage::new(
+                "resolve-identifier-bound-more-than-once-in-same-pattern",
+                "identifier `{identifier}` is bound more than once in the same pattern",
+            ).arg("identifier", identifier);
+            let mut err = struct_span_err_localized!(resolver.session, span, E0416, msg);
+            err.localized_span_label(span, LocalizedMessage::new(
+                "resolve-used-in-a-pattern-more-than-once",
+                "used in a pattern more than once",
+            ));
             err
         }
         ResolutionError::UndeclaredLabel(name, lev_candidate) => {
@@ -849,6 +849,9 @@ fn typeck_tables_of<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
     });
     let body = tcx.hir().body(body_id);
 
+    // With `-Z group-diagnostics`, the errors of the same kind in the body are grouped.
+    let _origin = errors::set_diagnostic_origin(tcx.def_path_hash(def_id).0.to_smaller_hash());
+
     let tables = Inherited::build(tcx, def_id).enter(|inh| {
         let param_env = tcx.param_env(def_id);
         let fcx = if let Some(decl) = fn_decl {
@@ -41,6 +41,7 @@ use rustc::util::captures::Captures;
 use rustc::util::nodemap::FxHashMap;
 use rustc_data_structures::sync::Lrc;
 use rustc_target::spec::abi;
+use errors::LocalizedMessage;
 
 use syntax::ast;
 use syntax::ast::MetaItemKind;
@@ -581,15 +582,16 @@ fn convert_variant<'a, 'tcx>(
             let fid = tcx.hir().local_def_id(f.id);
             let dup_span = seen_fields.get(&f.ident.modern()).cloned();
             if let Some(prev_span) = dup_span {
-                struct_span_err!(
-                    tcx.sess,
-                    f.span,
-                    E0124,
-                    "field `{}` is already declared",
-                    f.ident
-                ).span_label(f.span, "field already declared")
-                 .span_label(prev_span, format!("`{}` first declared here", f.ident))
-                 .emit();
+                let msg = LocalizedMessage::new("typeck-field-already-declared",
+                                                "field `{field}` is already declared")
+                    .arg("field", f.ident);
+                struct_span_err_localized!(tcx.sess, f.span, E0124, msg)
+                    .localized_span_label(f.span, LocalizedMessage::new(
+                        "typeck-field-already-declared-label", "field already declared"))
+                    .localized_span_label(prev_span, LocalizedMessage::new(
+                        "typeck-field-first-declared-here", "`{field}` first declared here",
+                    ).arg("field", f.ident))
+                    .emit();
             } else {
                 seen_fields.insert(f.ident.modern(), f.span);
             }
@@ -0,0 +1,694 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Comparison of the public API of two versions of a crate.
+//!
+//! The old version of the crate is given as the JSON document written by `--output-format json`,
+//! the new version is the crate being documented. Both are compared through their JSON form:
+//! every public item is recorded under its path, with the ids of the items it refers to replaced
+//! by their paths. The names of arguments and of generic parameters are left out, since renaming
+//! them doesn't change the API.
+//!
+//! Changes are classified following the rules of [RFC 1105]: removing or changing an item is a
+//! major change, adding one is a minor change, unless the addition breaks code that matches on or
+//! constructs the parent item (a field of a struct without private fields, an enum variant) or
+//! implements it (a required trait item).
+//!
+//! [RFC 1105]: https://github.com/rust-lang/rfcs/blob/master/text/1105-api-evolution.md
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use serialize::json::{self, Json, ToJson};
+
+use clean;
+use config::{ApiDiffFormat, RenderOptions};
+use html::render::{Cache, RenderInfo};
+use json as json_output;
+
+/// Keys of the JSON form of an item that list the ids of its children. The children are compared
+/// on their own, so these keys are left out of the signature of their parent.
+const CHILD_KEYS: &[&str] = &["items", "fields", "variants", "impls", "implementors",
+                              "provided_trait_methods"];
+
+/// Compares the crate against the JSON document named by `--api-diff` and prints the changes to
+/// its public API.
+pub fn run(krate: clean::Crate,
+           options: RenderOptions,
+           renderinfo: RenderInfo) -> Result<(), String> {
+    let baseline_path = options.api_diff.clone().expect("no baseline given to the API diff");
+    let baseline = load_baseline(&baseline_path)?;
+
+    let (krate, cache) = Cache::from_krate(renderinfo, &options.extern_html_root_urls,
+                                           &options.output, false, krate);
+    let current = json_output::document(&krate, &cache);
+
+    let old = Collector::collect(&baseline)
+        .map_err(|e| format!("`{}` is malformed: {}", baseline_path.display(), e))?;
+    let new = Collector::collect(&current)?;
+    let changes = diff(&old, &new);
+
+    match options.api_diff_format {
+        ApiDiffFormat::Text => print_text(&changes),
+        ApiDiffFormat::Json => println!("{}", changes_to_json(&changes)),
+    }
+    Ok(())
+}
+
+fn load_baseline(path: &Path) -> Result<Json, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("couldn't read `{}`: {}", path.display(), e))?;
+    let baseline = json::from_str(&contents)
+        .map_err(|e| format!("couldn't parse `{}`: {}", path.display(), e))?;
+    let version = baseline.find("format_version").and_then(|v| v.as_u64());
+    if version != Some(json_output::FORMAT_VERSION as u64) {
+        return Err(format!("`{}` was not written by this version of rustdoc's JSON output \
+                            (expected format version {})",
+                           path.display(), json_output::FORMAT_VERSION));
+    }
+    Ok(baseline)
+}
+
+/// An item of the public API.
+struct ApiItem {
+    /// The item type, as written in the `kind` field of the JSON output.
+    kind: String,
+    /// The kind-specific part of the item, without its children.
+    signature: Json,
+    /// The trait impls of a type, keyed by their header with its generic parameters renamed, and
+    /// holding their header as written along with their generics.
+    impls: BTreeMap<String, (String, Json)>,
+    /// The path of the item this one is a member of, for fields, variants and associated items.
+    parent: Option<String>,
+}
+
+/// The placeholders replacing the names of the generic parameters in scope, by name.
+type GenericNames = BTreeMap<String, String>;
+
+/// Walks a JSON document to find its public API.
+struct Collector<'a> {
+    index: Option<&'a json::Object>,
+    paths: Option<&'a json::Object>,
+    api: BTreeMap<String, ApiItem>,
+}
+
+impl<'a> Collector<'a> {
+    fn collect(doc: &'a Json) -> Result<BTreeMap<String, ApiItem>, String> {
+        let mut collector = Collector {
+            index: doc.find("index").and_then(|i| i.as_object()),
+            paths: doc.find("paths").and_then(|p| p.as_object()),
+            api: BTreeMap::new(),
+        };
+        if let Some(root) = doc.find("root").and_then(|id| collector.item(id)) {
+            collector.visit(root, "", None, false, &GenericNames::new())?;
+        }
+        Ok(collector.api)
+    }
+
+    fn item(&self, id: &Json) -> Option<&'a Json> {
+        id.as_string().and_then(|id| self.index.and_then(|index| index.get(id)))
+    }
+
+    fn children(&self, inner: &'a Json, key: &str) -> Vec<&'a Json> {
+        inner.find(key)
+             .and_then(|ids| ids.as_array())
+             .map_or(vec![], |ids| ids.iter().filter_map(|id| self.item(id)).collect())
+    }
+
+    /// Records `item` and its public children. `inherits_visibility` is set for the items that
+    /// are as visible as their parent, like enum variants and trait items, and `outer` holds the
+    /// generic parameters of the parent.
+    fn visit(&mut self,
+             item: &'a Json,
+             prefix: &str,
+             parent: Option<&str>,
+             inherits_visibility: bool,
+             outer: &GenericNames) -> Result<(), String> {
+        let is_public = match item.find("visibility") {
+            Some(&Json::String(ref vis)) if vis == "public" => true,
+            Some(&Json::String(ref vis)) if vis == "default" => inherits_visibility,
+            Some(&Json::Null) | None => inherits_visibility,
+            Some(_) => false,
+        };
+        let name = match item.find("name").and_then(|n| n.as_string()) {
+            Some(name) if is_public => name,
+            _ => return Ok(()),
+        };
+        let path = if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}::{}", prefix, name)
+        };
+        let kind = item.find("kind").and_then(|k| k.as_string()).unwrap_or("").to_string();
+        let inner = match item.find("inner") {
+            Some(inner) => inner,
+            None => return Ok(()),
+        };
+        let names = generic_names(inner, outer);
+
+        let mut impls = BTreeMap::new();
+        match &kind[..] {
+            "mod" => {
+                for child in self.children(inner, "items") {
+                    self.visit(child, &path, None, false, &GenericNames::new())?;
+                }
+            }
+            "struct" | "union" => {
+                for child in self.children(inner, "fields") {
+                    self.visit(child, &path, Some(&path[..]), false, &names)?;
+                }
+            }
+            "enum" => {
+                for child in self.children(inner, "variants") {
+                    self.visit(child, &path, Some(&path[..]), true, &names)?;
+                }
+            }
+            "variant" => {
+                if let Some(fields) = inner.find("struct") {
+                    for child in self.children(fields, "fields") {
+                        self.visit(child, &path, Some(&path[..]), true, &names)?;
+                    }
+                }
+            }
+            "trait" => {
+                for child in self.children(inner, "items") {
+                    self.visit(child, &path, Some(&path[..]), true, &names)?;
+                }
+            }
+            _ => {}
+        }
+        for impl_ in self.children(inner, "impls") {
+            let impl_ = match impl_.find("inner") {
+                Some(impl_) => impl_,
+                None => continue,
+            };
+            let impl_names = generic_names(impl_, &GenericNames::new());
+            match impl_.find("trait") {
+                Some(&Json::Null) | None => {
+                    for child in self.children(impl_, "items") {
+                        self.visit(child, &path, Some(&path[..]), false, &impl_names)?;
+                    }
+                }
+                Some(trait_) => {
+                    let for_ = impl_.find("for")
+                        .ok_or_else(|| format!("an impl of `{}` has no `for` type", path))?;
+                    let negative = impl_.find("negative").and_then(|n| n.as_boolean());
+                    let header = |names: &GenericNames| -> Result<String, String> {
+                        Ok(format!("impl {}{} for {}",
+                                   if negative == Some(true) { "!" } else { "" },
+                                   type_to_string(&self.normalize(trait_, names))?,
+                                   type_to_string(&self.normalize(for_, names))?))
+                    };
+                    let generics = impl_.find("generics")
+                        .map_or(Json::Null, |g| self.normalize(g, &impl_names));
+                    impls.insert(header(&impl_names)?, (header(&GenericNames::new())?, generics));
+                }
+            }
+        }
+
+        let signature = self.normalize(inner, &names);
+        self.api.insert(path, ApiItem {
+            kind,
+            signature,
+            impls,
+            parent: parent.map(|p| p.to_string()),
+        });
+        Ok(())
+    }
+
+    /// Replaces the ids in `json` by the paths of the items they refer to and the generic
+    /// parameters by their placeholder in `names`, and leaves out the children of the item and
+    /// the names of the arguments of functions.
+    fn normalize(&self, json: &Json, names: &GenericNames) -> Json {
+        match *json {
+            // Lifetimes are written as strings.
+            Json::String(ref lifetime) if lifetime.starts_with('\'') => {
+                names.get(lifetime).cloned().unwrap_or_else(|| lifetime.clone()).to_json()
+            }
+            Json::Object(ref map) => {
+                let mut normalized = map.iter()
+                    .filter(|&(key, _)| !CHILD_KEYS.contains(&&key[..]))
+                    .map(|(key, value)| (key.clone(), self.normalize(value, names)))
+                    .collect::<json::Object>();
+                // The arguments of a function declaration are `[name, type]` pairs.
+                if map.contains_key("variadic") {
+                    if let Some(inputs) = map.get("inputs").and_then(|i| i.as_array()) {
+                        let types = inputs.iter().map(|input| {
+                            let ty = input.as_array().and_then(|pair| pair.get(1));
+                            self.normalize(ty.unwrap_or(input), names)
+                        }).collect();
+                        normalized.insert("inputs".to_string(), Json::Array(types));
+                    }
+                }
+                // Generic parameters are declared as `{ "name": ..., "kind": ... }` and used as
+                // `{ "generic": name }`.
+                for &key in &["name", "generic"] {
+                    let is_param = key == "generic" || map.len() == 2 && map.contains_key("kind");
+                    let placeholder = match map.get(key).and_then(|n| n.as_string()) {
+                        Some(name) if is_param => names.get(name),
+                        _ => None,
+                    };
+                    if let Some(placeholder) = placeholder {
+                        normalized.insert(key.to_string(), placeholder.to_json());
+                    }
+                }
+                if let Some(id) = map.get("id") {
+                    let path = self.path(id).map_or(Json::Null, |p| p.join("::").to_json());
+                    normalized.insert("id".to_string(), path);
+                    // The path as written in the source can change without the type changing.
+                    if normalized.contains_key("is_generic") {
+                        normalized.remove("name");
+                    }
+                }
+                Json::Object(normalized)
+            }
+            Json::Array(ref values) => {
+                Json::Array(values.iter().map(|v| self.normalize(v, names)).collect())
+            }
+            ref other => other.clone(),
+        }
+    }
+
+    fn path(&self, id: &Json) -> Option<Vec<&'a str>> {
+        let id = id.as_string()?;
+        let path = self.paths?.get(id)?.find("path")?.as_array()?;
+        path.iter().map(|segment| segment.as_string()).collect()
+    }
+}
+
+/// Returns the names in scope in the item whose kind-specific part is `inner`: the generic
+/// parameters of `outer`, followed by the ones the item declares. Each of them is replaced by a
+/// placeholder made of its position.
+fn generic_names(inner: &Json, outer: &GenericNames) -> GenericNames {
+    let mut names = outer.clone();
+    let params = inner.find_path(&["generics", "params"]).and_then(|p| p.as_array());
+    for param in params.into_iter().flat_map(|params| params.iter()) {
+        if let Some(name) = param.find("name").and_then(|n| n.as_string()) {
+            let sigil = if name.starts_with('\'') { "'" } else { "" };
+            let placeholder = format!("{}#{}", sigil, names.len());
+            names.insert(name.to_string(), placeholder);
+        }
+    }
+    names
+}
+
+/// How much a change breaks the code using the crate.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Severity {
+    Minor,
+    Major,
+}
+
+impl Severity {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            Severity::Minor => "minor",
+            Severity::Major => "major",
+        }
+    }
+}
+
+struct Change {
+    severity: Severity,
+    /// One of "added", "removed" or "changed".
+    change: &'static str,
+    kind: String,
+    path: String,
+    /// What exactly changed: the parts of a changed item, or the header of an impl.
+    details: Vec<String>,
+}
+
+fn diff(old: &BTreeMap<String, ApiItem>, new: &BTreeMap<String, ApiItem>) -> Vec<Change> {
+    let mut changes = vec![];
+
+    for (path, old_item) in old {
+        let new_item = match new.get(path) {
+            Some(item) => item,
+            None => {
+                // Removing an item also removes its members, no need to list them too.
+                if old_item.parent.as_ref().map_or(true, |parent| new.contains_key(parent)) {
+                    changes.push(Change {
+                        severity: Severity::Major,
+                        change: "removed",
+                        kind: old_item.kind.clone(),
+                        path: path.clone(),
+                        details: vec![],
+                    });
+                }
+                continue;
+            }
+        };
+
+        // Giving a required trait method a default implementation allows more code to compile.
+        let gained_default = old_item.kind == "tymethod" && new_item.kind == "method";
+        if old_item.kind != new_item.kind && !gained_default {
+            changes.push(Change {
+                severity: Severity::Major,
+                change: "changed",
+                kind: new_item.kind.clone(),
+                path: path.clone(),
+                details: vec![format!("was a {}", describe_kind(&old_item.kind))],
+            });
+            continue;
+        }
+        if old_item.signature != new_item.signature {
+            let (severity, mut details) = compare_signatures(&old_item.signature,
+                                                             &new_item.signature);
+            if gained_default {
+                details.insert(0, "default implementation".to_string());
+            }
+            changes.push(Change {
+                severity,
+                change: "changed",
+                kind: new_item.kind.clone(),
+                path: path.clone(),
+                details,
+            });
+        }
+
+        for (key, &(ref old_header, ref old_generics)) in &old_item.impls {
+            let (severity, change, header) = match new_item.impls.get(key) {
+                None => (Severity::Major, "removed", old_header),
+                Some(&(ref new_header, ref new_generics)) if new_generics != old_generics => {
+                    (Severity::Major, "changed", new_header)
+                }
+                Some(_) => continue,
+            };
+            changes.push(Change {
+                severity,
+                change,
+                kind: "impl".to_string(),
+                path: path.clone(),
+                details: vec![header.clone()],
+            });
+        }
+        let added_impls = new_item.impls.iter().filter(|&(key, _)| {
+            !old_item.impls.contains_key(key)
+        });
+        for (_, &(ref header, _)) in added_impls {
+            changes.push(Change {
+                severity: Severity::Minor,
+                change: "added",
+                kind: "impl".to_string(),
+                path: path.clone(),
+                details: vec![header.clone()],
+            });
+        }
+    }
+
+    for (path, new_item) in new {
+        if old.contains_key(path) {
+            continue;
+        }
+        let severity = match new_item.parent.as_ref().map(|parent| old.get(parent)) {
+            // Adding an item also adds its members, no need to list them too.
+            Some(None) => continue,
+            Some(Some(parent)) => addition_severity(parent, new_item),
+            None => Severity::Minor,
+        };
+        changes.push(Change {
+            severity,
+            change: "added",
+            kind: new_item.kind.clone(),
+            path: path.clone(),
+            details: vec![],
+        });
+    }
+
+    changes.sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| a.path.cmp(&b.path)));
+    changes
+}
+
+/// Returns whether adding `item` to the already existing `parent` breaks code using `parent`.
+fn addition_severity(parent: &ApiItem, item: &ApiItem) -> Severity {
+    let is_required = match &item.kind[..] {
+        // Struct literals and patterns have to list every field, unless some are private.
+        "structfield" => match &parent.kind[..] {
+            "struct" => parent.signature.find("fields_stripped") != Some(&Json::Boolean(true)),
+            _ => true,
+        },
+        // Matches on the enum have to handle the new variant.
+        "variant" => true,
+        // Implementations of the trait have to provide the new item.
+        "tymethod" => true,
+        "associatedtype" | "associatedconstant" => {
+            parent.kind == "trait" && item.signature.find("default") == Some(&Json::Null)
+        }
+        _ => false,
+    };
+    if is_required { Severity::Major } else { Severity::Minor }
+}
+
+/// Compares the signatures of the two versions of an item, returning how breaking the change is
+/// along with the parts of the signature that changed.
+fn compare_signatures(old: &Json, new: &Json) -> (Severity, Vec<String>) {
+    let (old, new) = match (old.as_object(), new.as_object()) {
+        (Some(old), Some(new)) => (old, new),
+        _ => return (Severity::Major, vec![]),
+    };
+    let keys = old.keys().chain(new.keys()).collect::<BTreeSet<_>>();
+    let mut severity = Severity::Minor;
+    let mut details = vec![];
+    for key in keys {
+        let (old_value, new_value) = (old.get(key), new.get(key));
+        // Whether a trait method has a body is given by its kind, which `diff` compares.
+        if old_value == new_value || key == "has_body" {
+            continue;
+        }
+        // Making every field public allows more code to compile, anything else may break some.
+        let loosened = match (&key[..], old_value, new_value) {
+            ("fields_stripped", Some(&Json::Boolean(true)), Some(&Json::Boolean(false))) |
+            ("variants_stripped", Some(&Json::Boolean(true)), Some(&Json::Boolean(false))) => true,
+            _ => false,
+        };
+        if !loosened {
+            severity = Severity::Major;
+        }
+        details.push(key.replace('_', " "));
+    }
+    (severity, details)
+}
+
+fn describe_kind(kind: &str) -> &str {
+    match kind {
+        "mod" => "module",
+        "externcrate" => "extern crate",
+        "fn" => "function",
+        "type" => "type alias",
+        "tymethod" => "required method",
+        "structfield" => "field",
+        "associatedtype" => "associated type",
+        "associatedconstant" => "associated constant",
+        "foreigntype" => "foreign type",
+        "attr" => "attribute macro",
+        "derive" => "derive macro",
+        "existential" => "existential type",
+        kind => kind,
+    }
+}
+
+fn print_text(changes: &[Change]) {
+    for change in changes {
+        print!("{}: {} {} `{}`", change.severity.as_str(), change.change,
+               describe_kind(&change.kind), change.path);
+        match (&change.kind[..], change.details.is_empty()) {
+            (_, true) => println!(),
+            ("impl", false) => println!(": `{}`", change.details.join("`, `")),
+            (_, false) => println!(" ({})", change.details.join(", ")),
+        }
+    }
+
+    let count = |severity| changes.iter().filter(|c| c.severity == severity).count();
+    let (major, minor) = (count(Severity::Major), count(Severity::Minor));
+    if !changes.is_empty() {
+        println!();
+    }
+    println!("{} major and {} minor change{}, the next version needs a {} bump",
+             major, minor, if major + minor == 1 { "" } else { "s" }, bump(changes));
+}
+
+fn changes_to_json(changes: &[Change]) -> Json {
+    let changes_json = changes.iter().map(|change| {
+        let mut map = BTreeMap::new();
+        map.insert("severity".to_string(), change.severity.as_str().to_json());
+        map.insert("change".to_string(), change.change.to_json());
+        map.insert("kind".to_string(), change.kind.to_json());
+        map.insert("path".to_string(), change.path.to_json());
+        map.insert("details".to_string(), change.details.to_json());
+        Json::Object(map)
+    }).collect::<Vec<_>>();
+
+    let mut map = BTreeMap::new();
+    map.insert("changes".to_string(), Json::Array(changes_json));
+    map.insert("bump".to_string(), bump(changes).to_json());
+    Json::Object(map)
+}
+
+/// The part of the version number that has to be increased for the given changes.
+fn bump(changes: &[Change]) -> &'static str {
+    match changes.iter().map(|c| c.severity).max() {
+        Some(severity) => severity.as_str(),
+        None => "patch",
+    }
+}
+
+/// Renders a type of the JSON output, after its ids have been replaced by paths.
+fn type_to_string(ty: &Json) -> Result<String, String> {
+    let (variant, value) = match *ty {
+        Json::String(ref s) => return Ok(match &s[..] {
+            "never" => "!".to_string(),
+            "infer" => "_".to_string(),
+            s => s.to_string(),
+        }),
+        Json::Object(ref map) if map.len() == 1 => map.iter().next().unwrap(),
+        _ => return Ok("?".to_string()),
+    };
+    let field = |key: &str| value.find(key).unwrap_or(&Json::Null);
+    let types = |tys: &Json| -> Result<Vec<String>, String> {
+        tys.as_array().map_or(Ok(vec![]), |tys| tys.iter().map(type_to_string).collect())
+    };
+    Ok(match &variant[..] {
+        "resolved_path" => {
+            let name = field("id").as_string()
+                .and_then(|path| path.rsplit("::").next())
+                .or_else(|| field("name").as_string())
+                .unwrap_or("?");
+            format!("{}{}", name, generic_args_to_string(field("args"))?)
+        }
+        "generic" | "primitive" => value.as_string().unwrap_or("?").to_string(),
+        "tuple" => format!("({})", types(value)?.join(", ")),
+        "slice" => format!("[{}]", type_to_string(value)?),
+        "array" => format!("[{}; {}]", type_to_string(field("type"))?,
+                           field("len").as_string().unwrap_or("?")),
+        "raw_pointer" => {
+            let mutability = if field("mutable") == &Json::Boolean(true) { "mut" } else { "const" };
+            format!("*{} {}", mutability, type_to_string(field("type"))?)
+        }
+        "borrowed_ref" => {
+            let lifetime = field("lifetime").as_string().map(|l| format!("{} ", l));
+            let mutability = if field("mutable") == &Json::Boolean(true) { "mut " } else { "" };
+            format!("&{}{}{}", lifetime.unwrap_or_default(), mutability,
+                    type_to_string(field("type"))?)
+        }
+        "qualified_path" => {
+            format!("<{} as {}>::{}", type_to_string(field("self_type"))?,
+                    type_to_string(field("trait"))?, field("name").as_string().unwrap_or("?"))
+        }
+        "function_pointer" => {
+            // The names of the arguments have been left out by `Collector::normalize`.
+            let inputs = types(field("decl").find("inputs").unwrap_or(&Json::Null))?;
+            format!("fn({})", inputs.join(", "))
+        }
+        "impl_trait" => {
+            let bounds = value.as_array().map_or(Ok(vec![]), |bounds| {
+                bounds.iter().map(|bound| match bound.find("trait_bound") {
+                    Some(bound) => {
+                        let trait_ = bound.find("trait")
+                            .ok_or_else(|| "a trait bound has no `trait`".to_string())?;
+                        type_to_string(trait_)
+                    }
+                    None => Ok(bound.find("outlives").and_then(|l| l.as_string())
+                                    .unwrap_or("?").to_string()),
+                }).collect::<Result<Vec<_>, String>>()
+            })?;
+            format!("impl {}", bounds.join(" + "))
+        }
+        _ => "?".to_string(),
+    })
+}
+
+fn generic_args_to_string(args: &Json) -> Result<String, String> {
+    if let Some(args) = args.find("angle_bracketed") {
+        let mut parts = vec![];
+        if let Some(lifetimes) = args.find("lifetimes").and_then(|l| l.as_array()) {
+            parts.extend(lifetimes.iter().filter_map(|l| l.as_string()).map(|l| l.to_string()));
+        }
+        if let Some(types) = args.find("types").and_then(|t| t.as_array()) {
+            for ty in types {
+                parts.push(type_to_string(ty)?);
+            }
+        }
+        if let Some(bindings) = args.find("bindings").and_then(|b| b.as_array()) {
+            for binding in bindings {
+                let name = binding.find("name").and_then(|n| n.as_string()).unwrap_or("?");
+                let ty = binding.find("type")
+                    .ok_or_else(|| format!("the type binding `{}` has no `type`", name))?;
+                parts.push(format!("{} = {}", name, type_to_string(ty)?));
+            }
+        }
+        Ok(if parts.is_empty() { String::new() } else { format!("<{}>", parts.join(", ")) })
+    } else if let Some(args) = args.find("parenthesized") {
+        let inputs = args.find("inputs").and_then(|i| i.as_array()).map_or(Ok(vec![]), |inputs| {
+            inputs.iter().map(type_to_string).collect::<Result<Vec<_>, _>>()
+        })?;
+        Ok(match args.find("output") {
+            Some(output) if !output.is_null() => {
+                format!("({}) -> {}", inputs.join(", "), type_to_string(output)?)
+            }
+            _ => format!("({})", inputs.join(", ")),
+        })
+    } else {
+        Ok(String::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serialize::json::Json;
+
+    use super::{diff, ApiItem, Severity};
+
+    fn method(kind: &str, has_body: bool, generics: &str) -> ApiItem {
+        let mut signature = BTreeMap::new();
+        signature.insert("decl".to_string(), Json::String("fn(&self)".to_string()));
+        signature.insert("generics".to_string(), Json::String(generics.to_string()));
+        signature.insert("has_body".to_string(), Json::Boolean(has_body));
+        ApiItem {
+            kind: kind.to_string(),
+            signature: Json::Object(signature),
+            impls: BTreeMap::new(),
+            parent: Some("foo::Trait".to_string()),
+        }
+    }
+
+    fn api(item: ApiItem) -> BTreeMap<String, ApiItem> {
+        let mut api = BTreeMap::new();
+        api.insert("foo::Trait::method".to_string(), item);
+        api
+    }
+
+    #[test]
+    fn required_method_gaining_a_default_is_minor() {
+        let changes = diff(&api(method("tymethod", false, "")),
+                           &api(method("method", true, "")));
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].severity, Severity::Minor);
+        assert_eq!(changes[0].kind, "method");
+        assert_eq!(changes[0].details, ["default implementation"]);
+
+        // Anything else that changed along with it still counts.
+        let changes = diff(&api(method("tymethod", false, "")),
+                           &api(method("method", true, "<T>")));
+        assert_eq!(changes[0].severity, Severity::Major);
+        assert_eq!(changes[0].details, ["default implementation", "generics"]);
+    }
+
+    #[test]
+    fn provided_method_losing_its_default_is_major() {
+        let changes = diff(&api(method("method", true, "")),
+                           &api(method("tymethod", false, "")));
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].severity, Severity::Major);
+        assert_eq!(changes[0].details, ["was a method"]);
+    }
+}
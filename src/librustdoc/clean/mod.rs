@@ -43,7 +43,7 @@ use syntax::source_map::{dummy_spanned, Spanned};
 use syntax::ptr::P;
 use syntax::symbol::keywords::{self, Keyword};
 use syntax::symbol::InternedString;
-use syntax_pos::{self, DUMMY_SP, Pos, FileName};
+use syntax_pos::{self, DUMMY_SP, ExpnFormat, Pos, FileName};
 
 use std::collections::hash_map::Entry;
 use std::fmt;
@@ -761,6 +761,8 @@ pub struct Attributes {
     pub other_attrs: Vec<ast::Attribute>,
     pub cfg: Option<Arc<Cfg>>,
     pub span: Option<syntax_pos::Span>,
+    /// Where `span` points to, kept for the diagnostics emitted once the source map is gone.
+    pub doc_location: Option<Span>,
     /// map from Rust paths to resolved defs and potential URL fragments
     pub links: Vec<(String, Option<DefId>, Option<String>)>,
     pub inner_docs: bool,
@@ -918,6 +920,7 @@ impl Attributes {
             other_attrs,
             cfg: if cfg == Cfg::True { None } else { Some(Arc::new(cfg)) },
             span: sp,
+            doc_location: None,
             links: vec![],
             inner_docs,
         }
@@ -1014,7 +1017,9 @@ impl AttributesExt for Attributes {
 
 impl Clean<Attributes> for [ast::Attribute] {
     fn clean(&self, cx: &DocContext) -> Attributes {
-        Attributes::from_ast(cx.sess().diagnostic(), self)
+        let mut attrs = Attributes::from_ast(cx.sess().diagnostic(), self);
+        attrs.doc_location = attrs.span.map(|span| span.clean(cx));
+        attrs
     }
 }
 
@@ -2993,6 +2998,8 @@ impl Clean<VariantKind> for hir::VariantData {
     }
 }
 
+/// The location of an item. For items generated by a macro, this is the invocation of the macro
+/// written in the crate, and `expansions` lists the macros the item went through.
 #[derive(Clone, RustcEncodable, RustcDecodable, Debug)]
 pub struct Span {
     pub filename: FileName,
@@ -3000,6 +3007,8 @@ pub struct Span {
     pub locol: usize,
     pub hiline: usize,
     pub hicol: usize,
+    /// The macro expansions that produced the item, innermost first.
+    pub expansions: Vec<Expansion>,
 }
 
 impl Span {
@@ -3008,6 +3017,7 @@ impl Span {
             filename: FileName::Anon(0),
             loline: 0, locol: 0,
             hiline: 0, hicol: 0,
+            expansions: Vec::new(),
         }
     }
 }
@@ -3019,19 +3029,52 @@ impl Clean<Span> for syntax_pos::Span {
         }
 
         let cm = cx.sess().source_map();
-        let filename = cm.span_to_filename(*self);
-        let lo = cm.lookup_char_pos(self.lo());
-        let hi = cm.lookup_char_pos(self.hi());
+
+        // Walk up to the outermost macro invocation, which is where the item can be found in the
+        // source of the crate, rather than in the definition of the macro.
+        let mut span = *self;
+        let mut expansions = vec![];
+        while let Some(info) = span.ctxt().outer().expn_info() {
+            if info.call_site.is_dummy() {
+                break;
+            }
+            let macro_name = match info.format {
+                ExpnFormat::MacroBang(name) => format!("{}!", name),
+                ExpnFormat::MacroAttribute(name) => format!("#[{}]", name),
+                ExpnFormat::CompilerDesugaring(_) => break,
+            };
+            expansions.push(Expansion {
+                macro_name,
+                filename: cm.span_to_filename(info.call_site),
+                line: cm.lookup_char_pos(info.call_site.lo()).line,
+            });
+            span = info.call_site;
+        }
+
+        let filename = cm.span_to_filename(span);
+        let lo = cm.lookup_char_pos(span.lo());
+        let hi = cm.lookup_char_pos(span.hi());
         Span {
             filename,
             loline: lo.line,
             locol: lo.col.to_usize(),
             hiline: hi.line,
             hicol: hi.col.to_usize(),
+            expansions,
         }
     }
 }
 
+/// An invocation of a macro, recorded in the `Span` of the items it generated.
+#[derive(Clone, RustcEncodable, RustcDecodable, Debug)]
+pub struct Expansion {
+    /// The name of the macro, like `foo!` or `#[derive(Debug)]`.
+    pub macro_name: String,
+    /// Where the macro was invoked.
+    pub filename: FileName,
+    pub line: usize,
+}
+
 #[derive(Clone, RustcEncodable, RustcDecodable, PartialEq, Eq, Debug, Hash)]
 pub struct Path {
     pub global: bool,
@@ -3541,14 +3584,31 @@ impl Clean<Vec<Item>> for doctree::Import {
             Import::Simple(name.clean(cx), resolve_use_source(cx, path))
         };
 
+        // A re-export that isn't inlined still shows whether what it re-exports is deprecated,
+        // unstable or only available on some platforms.
+        let target = match inner {
+            Import::Simple(_, ImportSource { did: Some(did), .. }) => Some(did),
+            _ => None,
+        };
+        let mut attrs = self.attrs.clean(cx);
+        if let Some(target_cfg) = target.and_then(|did| inline::load_attrs(cx, did).cfg) {
+            attrs.cfg = Some(match attrs.cfg.take() {
+                Some(mut cfg) => {
+                    *Arc::make_mut(&mut cfg) &= Cfg::clone(&target_cfg);
+                    cfg
+                }
+                None => target_cfg,
+            });
+        }
+
         vec![Item {
             name: None,
-            attrs: self.attrs.clean(cx),
+            attrs,
             source: self.whence.clean(cx),
             def_id: cx.tcx.hir().local_def_id(ast::CRATE_NODE_ID),
             visibility: self.vis.clean(cx),
-            stability: None,
-            deprecation: None,
+            stability: target.and_then(|did| get_stability(cx, did)),
+            deprecation: target.and_then(|did| get_deprecation(cx, did)),
             inner: ImportItem(inner)
         }]
     }
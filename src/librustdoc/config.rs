@@ -78,6 +78,9 @@ pub struct Options {
     pub should_test: bool,
     /// List of arguments to pass to the test harness, if running tests.
     pub test_args: Vec<String>,
+    /// Whether to compile the doctests that allow it into a single test harness, instead of
+    /// compiling every doctest on its own.
+    pub merge_doctests: bool,
 
     // Options that affect the documentation process
 
@@ -131,6 +134,7 @@ impl fmt::Debug for Options {
             .field("lint_cap", &self.lint_cap)
             .field("should_test", &self.should_test)
             .field("test_args", &self.test_args)
+            .field("merge_doctests", &self.merge_doctests)
             .field("default_passes", &self.default_passes)
             .field("manual_passes", &self.manual_passes)
             .field("display_warnings", &self.display_warnings)
@@ -145,6 +149,25 @@ impl fmt::Debug for Options {
 pub struct RenderOptions {
     /// Output directory to generate docs into. Defaults to `doc`.
     pub output: PathBuf,
+    /// The format to write the documentation in. Defaults to HTML.
+    pub output_format: OutputFormat,
+    /// Whether to print how many items are documented and have code examples instead of writing
+    /// documentation. The report is written as JSON if `output_format` is JSON.
+    pub show_coverage: bool,
+    /// If present, the JSON documentation of an older version of the crate. Instead of writing
+    /// documentation, rustdoc prints the changes made to the public API since that version.
+    pub api_diff: Option<PathBuf>,
+    /// The format to print the changes found by `api_diff` in.
+    pub api_diff_format: ApiDiffFormat,
+    /// The root files of example crates and tests to search for calls to the documented functions
+    /// and methods. The calls found are shown in the documentation of the items they call.
+    pub scrape_examples: Vec<PathBuf>,
+    /// Whether to write a full-text index of the documentation, to let the search look for words
+    /// in the documentation of items and not only in their names.
+    pub full_text_search: bool,
+    /// Whether to check that the relative links of the documentation point to pages and anchors
+    /// that were rendered, and warn about the ones that don't.
+    pub check_links: bool,
     /// External files to insert into generated pages.
     pub external_html: ExternalHtml,
     /// A pre-populated `IdMap` with the default headings and any headings added by Markdown files
@@ -194,6 +217,24 @@ pub struct RenderOptions {
     pub markdown_playground_url: Option<String>,
 }
 
+/// The formats rustdoc can write documentation in, selected with `--output-format`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// A set of HTML pages, one per item.
+    Html,
+    /// A single JSON file holding the cleaned crate. Unstable.
+    Json,
+}
+
+/// The formats the changes found by `--api-diff` can be printed in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApiDiffFormat {
+    /// One line per change, followed by a summary.
+    Text,
+    /// A JSON object listing the changes.
+    Json,
+}
+
 impl Options {
     /// Parses the given command-line for options. If an error message or other early-return has
     /// been printed, returns `Err` with the exit code.
@@ -241,6 +282,12 @@ impl Options {
             Some("json") => ErrorOutputType::Json(false),
             Some("pretty-json") => ErrorOutputType::Json(true),
             Some("short") => ErrorOutputType::Short(color),
+            // rustdoc creates several diagnostic handlers in one run, each of which would write
+            // its own SARIF log.
+            Some("sarif") => {
+                early_error(ErrorOutputType::default(),
+                            "`--error-format sarif` is not supported by rustdoc yet");
+            }
             None => ErrorOutputType::HumanReadable(color),
             Some(arg) => {
                 early_error(ErrorOutputType::default(),
@@ -320,6 +367,7 @@ impl Options {
                                               .collect();
 
         let should_test = matches.opt_present("test");
+        let merge_doctests = matches.opt_present("merge-doctests");
 
         let output = matches.opt_str("o")
                             .map(|s| PathBuf::from(&s))
@@ -387,12 +435,42 @@ impl Options {
             }
         }
 
-        match matches.opt_str("w").as_ref().map(|s| &**s) {
-            Some("html") | None => {}
+        let output_format = match matches.opt_str("w").as_ref().map(|s| &**s) {
+            Some("html") | None => OutputFormat::Html,
+            Some("json") => {
+                if !nightly_options::is_unstable_enabled(matches) {
+                    diag.struct_err("the `-Z unstable-options` flag must also be passed to \
+                                     enable `--output-format json`").emit();
+                    return Err(1);
+                }
+                OutputFormat::Json
+            }
             Some(s) => {
                 diag.struct_err(&format!("unknown output format: {}", s)).emit();
                 return Err(1);
             }
+        };
+
+        let show_coverage = matches.opt_present("show-coverage");
+        let api_diff = matches.opt_str("api-diff").map(PathBuf::from);
+        let api_diff_format = match matches.opt_str("api-diff-format").as_ref().map(|s| &**s) {
+            Some("text") | None => ApiDiffFormat::Text,
+            Some("json") => ApiDiffFormat::Json,
+            Some(s) => {
+                diag.struct_err(&format!("unknown API diff format: {}", s)).emit();
+                return Err(1);
+            }
+        };
+
+        let scrape_examples = matches.opt_strs("scrape-examples").into_iter()
+            .map(PathBuf::from)
+            .collect::<Vec<_>>();
+        for path in &scrape_examples {
+            if !path.is_file() {
+                diag.struct_err(&format!("option `--scrape-examples` argument must be a file: {}",
+                                         path.display())).emit();
+                return Err(1);
+            }
         }
 
         let index_page = matches.opt_str("index-page").map(|s| PathBuf::from(&s));
@@ -433,6 +511,8 @@ impl Options {
         let markdown_playground_url = matches.opt_str("markdown-playground-url");
         let crate_version = matches.opt_str("crate-version");
         let enable_index_page = matches.opt_present("enable-index-page") || index_page.is_some();
+        let full_text_search = matches.opt_present("enable-full-text-search");
+        let check_links = matches.opt_present("check-links");
 
         let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(matches, error_format);
 
@@ -454,12 +534,20 @@ impl Options {
             lint_cap,
             should_test,
             test_args,
+            merge_doctests,
             default_passes,
             manual_passes,
             display_warnings,
             crate_version,
             render_options: RenderOptions {
                 output,
+                output_format,
+                show_coverage,
+                api_diff,
+                api_diff_format,
+                scrape_examples,
+                full_text_search,
+                check_links,
                 external_html,
                 id_map,
                 playground_url,
@@ -489,7 +577,6 @@ impl Options {
 fn check_deprecated_options(matches: &getopts::Matches, diag: &errors::Handler) {
     let deprecated_flags = [
        "input-format",
-       "output-format",
        "no-defaults",
        "passes",
     ];
@@ -305,6 +305,7 @@ pub fn new_handler(error_format: ErrorOutputType,
                 true,
                 false)
         ),
+        ErrorOutputType::Sarif => unreachable!("rustdoc rejects `--error-format sarif`"),
     };
 
     errors::Handler::with_emitter_and_flags(
@@ -0,0 +1,215 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Documentation coverage report, printed by `--show-coverage`.
+//!
+//! Every item left in the crate once the passes have run is counted, so with the default passes
+//! the report covers the public API. Impl blocks are not counted themselves: the items of
+//! inherent impls are, while trait impls are skipped since their items are documented on the
+//! trait.
+
+use std::collections::BTreeMap;
+use std::ops;
+
+use serialize::json::{Json, ToJson};
+
+use clean;
+use config::OutputFormat;
+use html::markdown::{find_testable_code, ErrorCodes, LangString};
+
+/// The counts of items for a module, a file or the whole crate.
+#[derive(Default, Copy, Clone)]
+struct ItemCount {
+    total: u64,
+    with_docs: u64,
+    with_examples: u64,
+}
+
+impl ItemCount {
+    fn count_item(&mut self, has_docs: bool, has_examples: bool) {
+        self.total += 1;
+        if has_docs {
+            self.with_docs += 1;
+        }
+        if has_examples {
+            self.with_examples += 1;
+        }
+    }
+
+    fn percentage(&self, count: u64) -> f64 {
+        if self.total > 0 {
+            (count as f64 * 100.0) / self.total as f64
+        } else {
+            100.0
+        }
+    }
+}
+
+impl ops::AddAssign for ItemCount {
+    fn add_assign(&mut self, rhs: Self) {
+        self.total += rhs.total;
+        self.with_docs += rhs.with_docs;
+        self.with_examples += rhs.with_examples;
+    }
+}
+
+impl ToJson for ItemCount {
+    fn to_json(&self) -> Json {
+        let mut map = BTreeMap::new();
+        map.insert("total".to_string(), self.total.to_json());
+        map.insert("with_docs".to_string(), self.with_docs.to_json());
+        map.insert("with_examples".to_string(), self.with_examples.to_json());
+        Json::Object(map)
+    }
+}
+
+#[derive(Default)]
+struct CoverageCalculator {
+    /// The path of the module being walked.
+    module: Vec<String>,
+    modules: BTreeMap<String, ItemCount>,
+    files: BTreeMap<String, ItemCount>,
+}
+
+impl CoverageCalculator {
+    fn visit(&mut self, item: &clean::Item) {
+        match item.inner {
+            // Stripped items are not part of the documentation, nor are imports. Trait impls
+            // are documented on the trait.
+            clean::StrippedItem(..) | clean::ImportItem(..) | clean::ExternCrateItem(..) => {}
+            clean::ImplItem(ref impl_) => {
+                if impl_.trait_.is_none() {
+                    for item in &impl_.items {
+                        self.visit(item);
+                    }
+                }
+            }
+            clean::ModuleItem(ref module) => {
+                self.module.push(item.name.clone().unwrap_or_default());
+                self.count(item);
+                for item in &module.items {
+                    self.visit(item);
+                }
+                self.module.pop();
+            }
+            _ => {
+                self.count(item);
+                let children = match item.inner {
+                    clean::StructItem(ref s) => &s.fields[..],
+                    clean::UnionItem(ref u) => &u.fields[..],
+                    clean::EnumItem(ref e) => &e.variants.raw[..],
+                    clean::VariantItem(clean::Variant {
+                        kind: clean::VariantKind::Struct(ref s),
+                    }) => &s.fields[..],
+                    clean::TraitItem(ref t) => &t.items[..],
+                    _ => &[],
+                };
+                for item in children {
+                    self.visit(item);
+                }
+            }
+        }
+    }
+
+    fn count(&mut self, item: &clean::Item) {
+        // Items inlined from other crates are covered by the report of their own crate.
+        if !item.def_id.is_local() {
+            return;
+        }
+
+        let docs = item.collapsed_doc_value();
+        let has_docs = docs.as_ref().map_or(false, |docs| !docs.trim().is_empty());
+        let has_examples = docs.map_or(false, |docs| {
+            let mut tests = Tests { found_tests: 0 };
+            find_testable_code(&docs, &mut tests, ErrorCodes::No).is_ok() &&
+                tests.found_tests > 0
+        });
+
+        self.modules.entry(self.module.join("::")).or_default()
+            .count_item(has_docs, has_examples);
+        self.files.entry(item.source.filename.to_string()).or_default()
+            .count_item(has_docs, has_examples);
+    }
+
+    fn total(&self) -> ItemCount {
+        let mut total = ItemCount::default();
+        for count in self.files.values() {
+            total += *count;
+        }
+        total
+    }
+
+    fn print_table(&self, heading: &str, counts: &BTreeMap<String, ItemCount>) {
+        let line = format!("+-{0:->35}-+-{0:->10}-+-{0:->10}-+-{0:->10}-+-{0:->10}-+-{0:->10}-+",
+                           "");
+        println!("{}", line);
+        println!("| {:<35} | {:>10} | {:>10} | {:>10} | {:>10} | {:>10} |",
+                 heading, "Documented", "Percentage", "Examples", "Percentage", "Total");
+        println!("{}", line);
+        for (name, count) in counts {
+            // Keep the end of long names, it is the most specific part.
+            let name = if name.len() > 35 {
+                let mut start = name.len() - 32;
+                while !name.is_char_boundary(start) {
+                    start += 1;
+                }
+                format!("...{}", &name[start..])
+            } else {
+                name.clone()
+            };
+            print_row(&name, count);
+        }
+        println!("{}", line);
+        print_row("Total", &self.total());
+        println!("{}", line);
+    }
+
+    fn to_json(&self) -> Json {
+        let mut map = BTreeMap::new();
+        map.insert("modules".to_string(), self.modules.to_json());
+        map.insert("files".to_string(), self.files.to_json());
+        map.insert("total".to_string(), self.total().to_json());
+        Json::Object(map)
+    }
+}
+
+fn print_row(name: &str, count: &ItemCount) {
+    println!("| {:<35} | {:>10} | {:>9.1}% | {:>10} | {:>9.1}% | {:>10} |",
+             name, count.with_docs, count.percentage(count.with_docs),
+             count.with_examples, count.percentage(count.with_examples), count.total);
+}
+
+struct Tests {
+    found_tests: usize,
+}
+
+impl ::test::Tester for Tests {
+    fn add_test(&mut self, _: String, _: LangString, _: usize) {
+        self.found_tests += 1;
+    }
+}
+
+/// Prints how many of the items of `krate` are documented and have code examples, per module and
+/// per file.
+pub fn run(krate: &clean::Crate, format: OutputFormat) {
+    let mut calc = CoverageCalculator::default();
+    if let Some(ref module) = krate.module {
+        calc.visit(module);
+    }
+
+    match format {
+        OutputFormat::Html => {
+            calc.print_table("Module", &calc.modules);
+            println!();
+            calc.print_table("File", &calc.files);
+        }
+        OutputFormat::Json => println!("{}", calc.to_json()),
+    }
+}
@@ -0,0 +1,209 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Checks of the relative links of the rendered documentation, done with `--check-links`.
+//!
+//! The links of the documentation of each page are recorded while the page is rendered, and
+//! checked once the whole crate is written: a link is broken if no file was written where it
+//! points to, or if it points to an anchor that the target page doesn't have. Links with a
+//! scheme, like `https:`, and absolute paths are not checked, since they depend on where the
+//! documentation is hosted.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use errors;
+use rustc::util::nodemap::{FxHashMap, FxHashSet};
+
+use clean;
+use html::markdown;
+
+/// A link written in the documentation of an item.
+struct DocLink {
+    href: String,
+    /// The location of the documentation the link was written in.
+    location: Option<clean::Span>,
+}
+
+#[derive(Default)]
+pub struct LinkChecker {
+    /// The links of the page being rendered.
+    pending: Vec<DocLink>,
+    /// The links of the pages written so far, along with the file of their page.
+    links: Vec<(PathBuf, DocLink)>,
+}
+
+impl LinkChecker {
+    /// Records the relative links of `md`, the documentation of `item` rendered on the current
+    /// page.
+    pub fn add_links(&mut self, item: &clean::Item, md: &str) {
+        for href in markdown::rendered_links(md, &item.links()) {
+            if is_relative(&href) {
+                self.pending.push(DocLink {
+                    href,
+                    location: item.attrs.doc_location.clone(),
+                });
+            }
+        }
+    }
+
+    /// Attaches the links recorded since the previous page to `page`, the file they were written
+    /// to. If the page wasn't written, the links are dropped.
+    pub fn finish_page(&mut self, page: Option<&Path>) {
+        match page {
+            Some(page) => {
+                let links = self.pending.drain(..).map(|link| (page.to_path_buf(), link));
+                self.links.extend(links);
+            }
+            None => self.pending.clear(),
+        }
+    }
+
+    /// Warns about the links pointing to files or anchors that don't exist.
+    pub fn check(&self, diag: &errors::Handler) {
+        // The ids of the pages read so far, `None` if the page couldn't be read.
+        let mut page_ids = FxHashMap::<PathBuf, Option<FxHashSet<String>>>::default();
+        let mut seen = FxHashSet::default();
+
+        for &(ref page, ref link) in &self.links {
+            if !seen.insert((page, &link.href)) {
+                continue;
+            }
+
+            let (path, fragment) = match link.href.find('#') {
+                Some(pos) => (&link.href[..pos], &link.href[pos + 1..]),
+                None => (&*link.href, ""),
+            };
+            let path = path.split('?').next().unwrap();
+            let target = if path.is_empty() {
+                page.clone()
+            } else {
+                let mut target = normalize(&page.parent().unwrap().join(path));
+                if target.is_dir() {
+                    target.push("index.html");
+                }
+                target
+            };
+
+            let problem = if !target.is_file() {
+                format!("no file was written at `{}`", target.display())
+            } else if !fragment.is_empty() &&
+                      target.extension().map_or(false, |ext| ext == "html") {
+                let found = page_ids.entry(target.clone()).or_insert_with(|| {
+                    fs::read_to_string(&target).ok().map(|html| ids(&html))
+                });
+                match *found {
+                    Some(ref ids) if !ids.contains(fragment) => {
+                        format!("`{}` has no anchor `{}`", target.display(), fragment)
+                    }
+                    _ => continue,
+                }
+            } else {
+                continue
+            };
+
+            let mut warning = diag.struct_warn(&format!("broken link `{}`", link.href));
+            warning.note(&problem);
+            match link.location {
+                Some(ref location) => {
+                    warning.note(&format!("the link is in the documentation at {}:{}:{}",
+                                          location.filename,
+                                          location.loline,
+                                          location.locol + 1));
+                }
+                None => {
+                    warning.note(&format!("the link is on the page `{}`", page.display()));
+                }
+            }
+            warning.emit();
+        }
+    }
+}
+
+/// Returns whether `href` is relative to the page it is on.
+fn is_relative(href: &str) -> bool {
+    if href.starts_with('/') {
+        return false;
+    }
+    match href.find(|c: char| c == ':' || c == '/' || c == '?' || c == '#') {
+        Some(pos) => href[pos..].starts_with(|c: char| c != ':'),
+        None => true,
+    }
+}
+
+/// Removes the `.` and `..` components of `path` without looking at the file system, so that
+/// `a/missing/../b` is `a/b` even though `a/missing` doesn't exist.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            component => normalized.push(component.as_os_str()),
+        }
+    }
+    normalized
+}
+
+/// Returns the ids of the elements of `html`, along with the names of its `<a name="...">`
+/// anchors.
+fn ids(html: &str) -> FxHashSet<String> {
+    let mut ids = FxHashSet::default();
+    for attr in &[" id=", " name="] {
+        let mut rest = html;
+        while let Some(pos) = rest.find(attr) {
+            rest = &rest[pos + attr.len()..];
+            let quote = match rest.chars().next() {
+                Some(quote @ '"') | Some(quote @ '\'') => quote,
+                _ => continue,
+            };
+            if let Some(end) = rest[1..].find(quote) {
+                ids.insert(rest[1..end + 1].to_string());
+            }
+        }
+    }
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::{ids, is_relative, normalize};
+
+    #[test]
+    fn test_is_relative() {
+        for href in &["struct.Foo.html", "../bar/index.html#method.x", "#examples", "a?b:c"] {
+            assert!(is_relative(href), "{} is relative", href);
+        }
+        for href in &["https://example.com", "mailto:me@example.com", "/foo/index.html"] {
+            assert!(!is_relative(href), "{} is not relative", href);
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize(Path::new("doc/foo/./../bar/missing/../struct.Baz.html")),
+                   PathBuf::from("doc/bar/struct.Baz.html"));
+        assert_eq!(normalize(Path::new("doc/../../std/index.html")),
+                   PathBuf::from("../std/index.html"));
+    }
+
+    #[test]
+    fn test_ids() {
+        let found = ids("<h3 id='method.x' class='method'><a name=\"old\"></a>\
+                         <div id=\"main\"><span id=10>");
+        let mut found = found.into_iter().collect::<Vec<_>>();
+        found.sort();
+        assert_eq!(found, ["main", "method.x", "old"]);
+    }
+}
@@ -547,17 +547,23 @@ pub fn find_testable_code<T: test::Tester>(
     let mut prev_offset = 0;
     let mut nb_lines = 0;
     let mut register_header = None;
+    let mut block_index = 0;
     'main: while let Some(event) = parser.next() {
         match event {
             Event::Start(Tag::CodeBlock(s)) => {
-                let block_info = if s.is_empty() {
+                let mut block_info = if s.is_empty() {
                     LangString::all_false()
                 } else {
                     LangString::parse(&*s, error_codes)
                 };
+                let index = block_index;
+                block_index += 1;
                 if !block_info.rust {
                     continue
                 }
+                if block_info.check_output {
+                    block_info.expected_output = following_text_block(doc, index);
+                }
                 let mut test_s = String::new();
                 let mut offset = None;
                 loop {
@@ -606,6 +612,35 @@ pub fn find_testable_code<T: test::Tester>(
     Ok(())
 }
 
+/// Returns the contents of the code block following the code block number `index` of `doc`, if
+/// it is a `text` block.
+fn following_text_block(doc: &str, index: usize) -> Option<String> {
+    let mut parser = Parser::new(doc);
+    let mut current = 0;
+    while let Some(event) = parser.next() {
+        if let Event::Start(Tag::CodeBlock(lang)) = event {
+            if current == index + 1 {
+                let is_text = lang.split(|c: char| !(c == '_' || c == '-' || c.is_alphanumeric()))
+                    .any(|token| token == "text");
+                if !is_text {
+                    return None;
+                }
+                let mut text = String::new();
+                while let Some(event) = parser.next() {
+                    match event {
+                        Event::End(Tag::CodeBlock(_)) => break,
+                        Event::Text(ref s) => text.push_str(s),
+                        _ => {}
+                    }
+                }
+                return Some(text);
+            }
+            current += 1;
+        }
+    }
+    None
+}
+
 #[derive(Eq, PartialEq, Clone, Debug)]
 pub struct LangString {
     original: String,
@@ -617,7 +652,12 @@ pub struct LangString {
     pub compile_fail: bool,
     pub error_codes: Vec<String>,
     pub allow_fail: bool,
-    pub edition: Option<Edition>
+    pub edition: Option<Edition>,
+    /// Whether the output of the test must match the `text` block that follows it.
+    pub check_output: bool,
+    /// The contents of the `text` block following a `check_output` block, filled in by
+    /// `find_testable_code`.
+    pub expected_output: Option<String>,
 }
 
 impl LangString {
@@ -633,6 +673,8 @@ impl LangString {
             error_codes: Vec::new(),
             allow_fail: false,
             edition: None,
+            check_output: false,
+            expected_output: None,
         }
     }
 
@@ -657,6 +699,10 @@ impl LangString {
                 "no_run" => { data.no_run = true; seen_rust_tags = !seen_other_tags; }
                 "ignore" => { data.ignore = true; seen_rust_tags = !seen_other_tags; }
                 "allow_fail" => { data.allow_fail = true; seen_rust_tags = !seen_other_tags; }
+                "check_output" => {
+                    data.check_output = true;
+                    seen_rust_tags = !seen_other_tags;
+                }
                 "rust" => { data.rust = true; seen_rust_tags = true; }
                 "test_harness" => {
                     data.test_harness = true;
@@ -855,6 +901,25 @@ pub fn plain_summary_line(md: &str) -> String {
     s
 }
 
+/// Returns the text of `md` without its markup, leaving out code blocks.
+pub fn plain_text(md: &str) -> String {
+    let mut s = String::with_capacity(md.len());
+    let mut in_code_block = false;
+    for event in Parser::new(md) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(Tag::CodeBlock(_)) => in_code_block = false,
+            Event::Text(ref text) if !in_code_block => s.push_str(text),
+            // Inline markup is part of a word, blocks and line breaks are not.
+            Event::End(Tag::Code) | Event::End(Tag::Emphasis) | Event::End(Tag::Strong) |
+            Event::End(Tag::Link(..)) => {}
+            Event::End(_) | Event::SoftBreak | Event::HardBreak => s.push(' '),
+            _ => {}
+        }
+    }
+    s
+}
+
 pub fn markdown_links(md: &str) -> Vec<(String, Option<Range<usize>>)> {
     if md.is_empty() {
         return vec![];
@@ -911,6 +976,32 @@ pub fn markdown_links(md: &str) -> Vec<(String, Option<Range<usize>>)> {
     links
 }
 
+/// Returns the destinations of the links of `md` as they are written by `Markdown`, once the
+/// intra-doc links in `links` are replaced with their URL.
+pub fn rendered_links(md: &str, links: &[(String, String)]) -> Vec<String> {
+    if md.is_empty() {
+        return vec![];
+    }
+
+    let mut opts = Options::empty();
+    opts.insert(OPTION_ENABLE_TABLES);
+    opts.insert(OPTION_ENABLE_FOOTNOTES);
+
+    let replacer = |_: &str, s: &str| {
+        if let Some(&(_, ref replace)) = links.into_iter().find(|link| &*link.0 == s) {
+            Some((replace.clone(), s.to_owned()))
+        } else {
+            None
+        }
+    };
+
+    let p = Parser::new_with_broken_link_callback(md, opts, Some(&replacer));
+    LinkReplacer::new(p, links).filter_map(|ev| match ev {
+        Event::Start(Tag::Link(dest, _)) => Some(dest.into_owned()),
+        _ => None,
+    }).collect()
+}
+
 #[derive(Clone, Default, Debug)]
 pub struct IdMap {
     map: FxHashMap<String, usize>,
@@ -970,7 +1061,7 @@ fn test_unique_id() {
 #[cfg(test)]
 mod tests {
     use super::{ErrorCodes, LangString, Markdown, MarkdownHtml, IdMap};
-    use super::plain_summary_line;
+    use super::{plain_summary_line, plain_text, rendered_links};
     use std::cell::RefCell;
     use syntax::edition::Edition;
 
@@ -978,7 +1069,7 @@ mod tests {
     fn test_lang_string_parse() {
         fn t(s: &str,
             should_panic: bool, no_run: bool, ignore: bool, rust: bool, test_harness: bool,
-            compile_fail: bool, allow_fail: bool, error_codes: Vec<String>,
+            compile_fail: bool, allow_fail: bool, check_output: bool, error_codes: Vec<String>,
              edition: Option<Edition>) {
             assert_eq!(LangString::parse(s, ErrorCodes::Yes), LangString {
                 should_panic,
@@ -991,6 +1082,8 @@ mod tests {
                 original: s.to_owned(),
                 allow_fail,
                 edition,
+                check_output,
+                expected_output: None,
             })
         }
 
@@ -1000,24 +1093,25 @@ mod tests {
 
         // ignore-tidy-linelength
         // marker                | should_panic | no_run | ignore | rust | test_harness
-        //                       | compile_fail | allow_fail | error_codes | edition
-        t("",                      false,         false,   false,   true,  false, false, false, v(), None);
-        t("rust",                  false,         false,   false,   true,  false, false, false, v(), None);
-        t("sh",                    false,         false,   false,   false, false, false, false, v(), None);
-        t("ignore",                false,         false,   true,    true,  false, false, false, v(), None);
-        t("should_panic",          true,          false,   false,   true,  false, false, false, v(), None);
-        t("no_run",                false,         true,    false,   true,  false, false, false, v(), None);
-        t("test_harness",          false,         false,   false,   true,  true,  false, false, v(), None);
-        t("compile_fail",          false,         true,    false,   true,  false, true,  false, v(), None);
-        t("allow_fail",            false,         false,   false,   true,  false, false, true,  v(), None);
-        t("{.no_run .example}",    false,         true,    false,   true,  false, false, false, v(), None);
-        t("{.sh .should_panic}",   true,          false,   false,   false, false, false, false, v(), None);
-        t("{.example .rust}",      false,         false,   false,   true,  false, false, false, v(), None);
-        t("{.test_harness .rust}", false,         false,   false,   true,  true,  false, false, v(), None);
-        t("text, no_run",          false,         true,    false,   false, false, false, false, v(), None);
-        t("text,no_run",           false,         true,    false,   false, false, false, false, v(), None);
-        t("edition2015",           false,         false,   false,   true,  false, false, false, v(), Some(Edition::Edition2015));
-        t("edition2018",           false,         false,   false,   true,  false, false, false, v(), Some(Edition::Edition2018));
+        //                       | compile_fail | allow_fail | check_output | error_codes | edition
+        t("",                      false,         false,   false,   true,  false, false, false, false, v(), None);
+        t("rust",                  false,         false,   false,   true,  false, false, false, false, v(), None);
+        t("sh",                    false,         false,   false,   false, false, false, false, false, v(), None);
+        t("ignore",                false,         false,   true,    true,  false, false, false, false, v(), None);
+        t("should_panic",          true,          false,   false,   true,  false, false, false, false, v(), None);
+        t("no_run",                false,         true,    false,   true,  false, false, false, false, v(), None);
+        t("test_harness",          false,         false,   false,   true,  true,  false, false, false, v(), None);
+        t("compile_fail",          false,         true,    false,   true,  false, true,  false, false, v(), None);
+        t("allow_fail",            false,         false,   false,   true,  false, false, true,  false, v(), None);
+        t("check_output",          false,         false,   false,   true,  false, false, false, true,  v(), None);
+        t("{.no_run .example}",    false,         true,    false,   true,  false, false, false, false, v(), None);
+        t("{.sh .should_panic}",   true,          false,   false,   false, false, false, false, false, v(), None);
+        t("{.example .rust}",      false,         false,   false,   true,  false, false, false, false, v(), None);
+        t("{.test_harness .rust}", false,         false,   false,   true,  true,  false, false, false, v(), None);
+        t("text, no_run",          false,         true,    false,   false, false, false, false, false, v(), None);
+        t("text,no_run",           false,         true,    false,   false, false, false, false, false, v(), None);
+        t("edition2015",           false,         false,   false,   true,  false, false, false, false, v(), Some(Edition::Edition2015));
+        t("edition2018",           false,         false,   false,   true,  false, false, false, false, v(), Some(Edition::Edition2018));
     }
 
     #[test]
@@ -1079,6 +1173,29 @@ mod tests {
         t("## header", "header");
     }
 
+    #[test]
+    fn test_plain_text() {
+        fn t(input: &str, expect: &str) {
+            let output = plain_text(input);
+            assert_eq!(output.split_whitespace().collect::<Vec<_>>().join(" "), expect,
+                       "original: {}", input);
+        }
+
+        t("hello [Rust](https://www.rust-lang.org) :)", "hello Rust :)");
+        t("# header\n\nsome *emphasized* text", "header some emphasized text");
+        t("- first\n- second", "first second");
+        t("call `foo`:\n\n```\nfoo();\n```\n\nthen stop", "call foo: then stop");
+    }
+
+    #[test]
+    fn test_rendered_links() {
+        let links = [("Foo".to_string(), "struct.Foo.html".to_string())];
+        let md = "See [Foo], [bar](../bar/index.html#method.x), [baz][b] and [Unknown].\n\n\
+                  [b]: https://example.com/baz";
+        assert_eq!(rendered_links(md, &links),
+                   ["struct.Foo.html", "../bar/index.html#method.x", "https://example.com/baz"]);
+    }
+
     #[test]
     fn test_markdown_html_escape() {
         fn t(input: &str, expect: &str) {
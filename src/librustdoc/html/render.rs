@@ -75,10 +75,12 @@ use html::format::{GenericBounds, WhereClause, href, AbiSpace};
 use html::format::{VisSpace, Method, UnsafetySpace, MutableSpace};
 use html::format::fmt_impl_for_trait_page;
 use html::item_type::ItemType;
+use html::link_check::LinkChecker;
 use html::markdown::{self, Markdown, MarkdownHtml, MarkdownSummaryLine, ErrorCodes, IdMap};
-use html::{highlight, layout, static_files};
+use html::{highlight, layout, static_files, text_search};
 
 use minifier;
+use scrape_examples::{self, Example};
 
 /// A pair of name and its optional document.
 pub type NameDoc = (String, Option<String>);
@@ -140,6 +142,9 @@ struct SharedContext {
     /// Suffix to be added on resource files (if suffix is "-v2" then "light.css" becomes
     /// "light-v2.css").
     pub resource_suffix: String,
+    /// The links of the documentation rendered so far, when they are checked with
+    /// `--check-links`.
+    pub link_checker: Option<RefCell<LinkChecker>>,
 }
 
 impl SharedContext {
@@ -169,6 +174,21 @@ impl SharedContext {
             item.doc_value().map(|s| s.into())
         }
     }
+
+    /// Records the links of `md`, the documentation of `item`, if links are checked.
+    fn add_doc_links(&self, item: &clean::Item, md: &str) {
+        if let Some(ref checker) = self.link_checker {
+            checker.borrow_mut().add_links(item, md);
+        }
+    }
+
+    /// Attaches the links recorded since the previous page to `page`, the file that was just
+    /// written, or drops them if no file was written.
+    fn finish_page(&self, page: Option<&Path>) {
+        if let Some(ref checker) = self.link_checker {
+            checker.borrow_mut().finish_page(page);
+        }
+    }
 }
 
 /// Indicates where an external crate can be found.
@@ -188,7 +208,7 @@ pub struct Impl {
 }
 
 impl Impl {
-    fn inner_impl(&self) -> &clean::Impl {
+    pub fn inner_impl(&self) -> &clean::Impl {
         match self.impl_item.inner {
             clean::ImplItem(ref impl_) => impl_,
             _ => panic!("non-impl item found in impl")
@@ -319,6 +339,14 @@ pub struct Cache {
     /// The version of the crate being documented, if given from the `--crate-version` flag.
     pub crate_version: Option<String>,
 
+    /// Calls to the functions and methods of this crate found in the crates given with
+    /// `--scrape-examples`.
+    pub scraped_examples: FxHashMap<DefId, Vec<Example>>,
+
+    /// Whether the whole documentation of the items is kept in the search index, for
+    /// `--enable-full-text-search`.
+    full_text_search: bool,
+
     // Private fields only used when initially crawling a crate to build a cache
 
     stack: Vec<String>,
@@ -397,9 +425,13 @@ struct IndexItem {
     name: String,
     path: String,
     desc: String,
+    /// The whole documentation of the item, for the full-text index. Only kept with
+    /// `--enable-full-text-search`.
+    doc: Option<String>,
     parent: Option<DefId>,
     parent_idx: Option<usize>,
     search_type: Option<IndexItemFunctionType>,
+    badges: IndexItemBadges,
 }
 
 impl ToJson for IndexItem {
@@ -413,11 +445,61 @@ impl ToJson for IndexItem {
         data.push(self.desc.to_json());
         data.push(self.parent_idx.to_json());
         data.push(self.search_type.to_json());
+        // Most items have no badge, so the trailing elements are left out for them.
+        let flags = self.badges.flags();
+        if flags != 0 || self.badges.cfg.is_some() {
+            data.push(flags.to_json());
+            if let Some(ref cfg) = self.badges.cfg {
+                data.push(cfg.to_json());
+            }
+        }
 
         Json::Array(data)
     }
 }
 
+/// What the search results show about an item besides its name and description, which can be
+/// used to hide it from the results.
+#[derive(Debug, Default)]
+struct IndexItemBadges {
+    deprecated: bool,
+    unstable: bool,
+    /// The short description of the platforms the item is available on, if it isn't available
+    /// everywhere.
+    cfg: Option<String>,
+}
+
+impl IndexItemBadges {
+    const DEPRECATED: u8 = 1;
+    const UNSTABLE: u8 = 2;
+
+    fn new(item: &clean::Item) -> IndexItemBadges {
+        let stab = item.stability.as_ref();
+        let deprecated_since = stab.map(|stab| &stab.deprecated_since)
+            .filter(|since| !since.is_empty())
+            .or_else(|| item.deprecation.as_ref().map(|depr| &depr.since));
+        IndexItemBadges {
+            deprecated: deprecated_since.map_or(false, |since| {
+                stability::deprecation_in_effect(since)
+            }),
+            unstable: stab.map_or(false, |stab| stab.level == stability::Unstable),
+            cfg: item.attrs.cfg.as_ref().map(|cfg| cfg.render_short_html()),
+        }
+    }
+
+    /// The badges other than `cfg`, as the bit flags written in the search index.
+    fn flags(&self) -> u8 {
+        let mut flags = 0;
+        if self.deprecated {
+            flags |= IndexItemBadges::DEPRECATED;
+        }
+        if self.unstable {
+            flags |= IndexItemBadges::UNSTABLE;
+        }
+        flags
+    }
+}
+
 /// A type used for the search index.
 #[derive(Debug)]
 struct Type {
@@ -506,6 +588,9 @@ pub fn run(mut krate: clean::Crate,
         extension_css,
         extern_html_root_urls,
         resource_suffix,
+        scrape_examples: example_crates,
+        full_text_search,
+        check_links,
         ..
     } = options;
 
@@ -533,6 +618,7 @@ pub fn run(mut krate: clean::Crate,
         sort_modules_alphabetically,
         themes,
         resource_suffix,
+        link_checker: if check_links { Some(Default::default()) } else { None },
     };
 
     // If user passed in `--playground-url` arg, we fill in crate name here
@@ -583,90 +669,18 @@ pub fn run(mut krate: clean::Crate,
     };
 
     // Crawl the crate to build various caches used for the output
-    let RenderInfo {
-        inlined: _,
-        external_paths,
-        external_typarams,
-        exact_paths,
-        access_levels,
-        deref_trait_did,
-        deref_mut_trait_did,
-        owned_box_did,
-    } = renderinfo;
-
-    let external_paths = external_paths.into_iter()
-        .map(|(k, (v, t))| (k, (v, ItemType::from(t))))
-        .collect();
-
-    let mut cache = Cache {
-        impls: Default::default(),
-        external_paths,
-        exact_paths,
-        paths: Default::default(),
-        implementors: Default::default(),
-        stack: Vec::new(),
-        parent_stack: Vec::new(),
-        search_index: Vec::new(),
-        parent_is_trait_impl: false,
-        extern_locations: Default::default(),
-        primitive_locations: Default::default(),
-        stripped_mod: false,
-        access_levels,
-        crate_version: krate.version.take(),
-        orphan_impl_items: Vec::new(),
-        orphan_trait_impls: Vec::new(),
-        traits: krate.external_traits.lock().replace(Default::default()),
-        deref_trait_did,
-        deref_mut_trait_did,
-        owned_box_did,
-        masked_crates: mem::replace(&mut krate.masked_crates, Default::default()),
-        typarams: external_typarams,
-        aliases: Default::default(),
-    };
-
-    // Cache where all our extern crates are located
-    for &(n, ref e) in &krate.externs {
-        let src_root = match e.src {
-            FileName::Real(ref p) => match p.parent() {
-                Some(p) => p.to_path_buf(),
-                None => PathBuf::new(),
-            },
-            _ => PathBuf::new(),
-        };
-        let extern_url = extern_html_root_urls.get(&e.name).map(|u| &**u);
-        cache.extern_locations.insert(n, (e.name.clone(), src_root,
-                                          extern_location(e, extern_url, &cx.dst)));
-
-        let did = DefId { krate: n, index: CRATE_DEF_INDEX };
-        cache.external_paths.insert(did, (vec![e.name.to_string()], ItemType::Module));
-    }
-
-    // Cache where all known primitives have their documentation located.
-    //
-    // Favor linking to as local extern as possible, so iterate all crates in
-    // reverse topological order.
-    for &(_, ref e) in krate.externs.iter().rev() {
-        for &(def_id, prim, _) in &e.primitives {
-            cache.primitive_locations.insert(prim, def_id);
-        }
-    }
-    for &(def_id, prim, _) in &krate.primitives {
-        cache.primitive_locations.insert(prim, def_id);
-    }
-
-    cache.stack.push(krate.name.clone());
-    krate = cache.fold_crate(krate);
-
-    for (trait_did, dids, impl_) in cache.orphan_trait_impls.drain(..) {
-        if cache.traits.contains_key(&trait_did) {
-            for did in dids {
-                cache.impls.entry(did).or_insert(vec![]).push(impl_.clone());
-            }
-        }
-    }
+    let (krate, mut cache) = Cache::from_krate(renderinfo, &extern_html_root_urls, &cx.dst,
+                                               full_text_search, krate);
+    cache.scraped_examples = scrape_examples::scrape(&example_crates, &krate.name, &cache, diag);
 
     // Build our search index
     let index = build_index(&krate, &mut cache);
+    let text_index = if full_text_search {
+        let docs = cache.search_index.iter().map(|item| item.doc.as_ref().map_or("", |d| &**d));
+        Some(text_search::build_index(&krate.name, docs))
+    } else {
+        None
+    };
 
     // Freeze the cache now that the index has been built. Put an Arc into TLS
     // for future parallelization opportunities
@@ -674,10 +688,112 @@ pub fn run(mut krate: clean::Crate,
     CACHE_KEY.with(|v| *v.borrow_mut() = cache.clone());
     CURRENT_LOCATION_KEY.with(|s| s.borrow_mut().clear());
 
-    write_shared(&cx, &krate, &*cache, index, &md_opts, diag)?;
+    write_shared(&cx, &krate, &*cache, index, text_index, &md_opts, diag)?;
 
     // And finally render the whole crate's documentation
-    cx.krate(krate)
+    let shared = cx.shared.clone();
+    cx.krate(krate)?;
+
+    if let Some(ref checker) = shared.link_checker {
+        checker.borrow().check(diag);
+    }
+    Ok(())
+}
+
+impl Cache {
+    /// Crawls the crate to build the various caches used when rendering it, and returns the
+    /// crate as it looks after the crawl.
+    pub fn from_krate(renderinfo: RenderInfo,
+                      extern_html_root_urls: &BTreeMap<String, String>,
+                      dst: &Path,
+                      full_text_search: bool,
+                      mut krate: clean::Crate) -> (clean::Crate, Cache) {
+        let RenderInfo {
+            inlined: _,
+            external_paths,
+            external_typarams,
+            exact_paths,
+            access_levels,
+            deref_trait_did,
+            deref_mut_trait_did,
+            owned_box_did,
+        } = renderinfo;
+
+        let external_paths = external_paths.into_iter()
+            .map(|(k, (v, t))| (k, (v, ItemType::from(t))))
+            .collect();
+
+        let mut cache = Cache {
+            impls: Default::default(),
+            external_paths,
+            exact_paths,
+            paths: Default::default(),
+            implementors: Default::default(),
+            stack: Vec::new(),
+            parent_stack: Vec::new(),
+            search_index: Vec::new(),
+            parent_is_trait_impl: false,
+            extern_locations: Default::default(),
+            primitive_locations: Default::default(),
+            stripped_mod: false,
+            access_levels,
+            crate_version: krate.version.take(),
+            orphan_impl_items: Vec::new(),
+            orphan_trait_impls: Vec::new(),
+            traits: krate.external_traits.lock().replace(Default::default()),
+            deref_trait_did,
+            deref_mut_trait_did,
+            owned_box_did,
+            masked_crates: mem::replace(&mut krate.masked_crates, Default::default()),
+            typarams: external_typarams,
+            aliases: Default::default(),
+            scraped_examples: Default::default(),
+            full_text_search,
+        };
+
+        // Cache where all our extern crates are located
+        for &(n, ref e) in &krate.externs {
+            let src_root = match e.src {
+                FileName::Real(ref p) => match p.parent() {
+                    Some(p) => p.to_path_buf(),
+                    None => PathBuf::new(),
+                },
+                _ => PathBuf::new(),
+            };
+            let extern_url = extern_html_root_urls.get(&e.name).map(|u| &**u);
+            cache.extern_locations.insert(n, (e.name.clone(), src_root,
+                                              extern_location(e, extern_url, dst)));
+
+            let did = DefId { krate: n, index: CRATE_DEF_INDEX };
+            cache.external_paths.insert(did, (vec![e.name.to_string()], ItemType::Module));
+        }
+
+        // Cache where all known primitives have their documentation located.
+        //
+        // Favor linking to as local extern as possible, so iterate all crates in
+        // reverse topological order.
+        for &(_, ref e) in krate.externs.iter().rev() {
+            for &(def_id, prim, _) in &e.primitives {
+                cache.primitive_locations.insert(prim, def_id);
+            }
+        }
+        for &(def_id, prim, _) in &krate.primitives {
+            cache.primitive_locations.insert(prim, def_id);
+        }
+
+        cache.stack.push(krate.name.clone());
+        krate = cache.fold_crate(krate);
+
+        for (trait_did, dids, impl_) in cache.orphan_trait_impls.drain(..) {
+            if cache.traits.contains_key(&trait_did) {
+                for did in dids {
+                    cache.impls.entry(did).or_insert(vec![]).push(impl_.clone());
+                }
+            }
+        }
+
+        (krate, cache)
+    }
 }
 
 /// Build the search index from the collected metadata
@@ -688,7 +804,8 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
 
     let Cache { ref mut search_index,
                 ref orphan_impl_items,
-                ref mut paths, .. } = *cache;
+                ref mut paths,
+                full_text_search, .. } = *cache;
 
     // Attach all orphan items to the type's definition if the type
     // has since been learned.
@@ -699,9 +816,11 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
                 name: item.name.clone().unwrap(),
                 path: fqp[..fqp.len() - 1].join("::"),
                 desc: plain_summary_line(item.doc_value()),
+                doc: full_text_doc(full_text_search, item),
                 parent: Some(did),
                 parent_idx: None,
                 search_type: get_index_search_type(&item),
+                badges: IndexItemBadges::new(&item),
             });
         }
     }
@@ -755,6 +874,7 @@ fn write_shared(
     krate: &clean::Crate,
     cache: &Cache,
     search_index: String,
+    text_index: Option<String>,
     options: &RenderOptions,
     diag: &errors::Handler,
 ) -> Result<(), Error> {
@@ -937,13 +1057,24 @@ themePicker.onblur = handleThemeButtonsBlur;
     }
 
     fn show_item(item: &IndexItem, krate: &str) -> String {
-        format!("{{'crate':'{}','ty':{},'name':'{}','desc':'{}','p':'{}'{}}}",
+        let mut badges = String::new();
+        if item.badges.deprecated {
+            badges.push_str(",'deprecated':true");
+        }
+        if item.badges.unstable {
+            badges.push_str(",'unstable':true");
+        }
+        if let Some(ref cfg) = item.badges.cfg {
+            badges.push_str(&format!(",'cfg':'{}'", cfg.replace("'", "\\'")));
+        }
+        format!("{{'crate':'{}','ty':{},'name':'{}','desc':'{}','p':'{}'{}{}}}",
                 krate, item.ty as usize, item.name, item.desc.replace("'", "\\'"), item.path,
                 if let Some(p) = item.parent_idx {
                     format!(",'parent':{}", p)
                 } else {
                     String::new()
-                })
+                },
+                badges)
     }
 
     let dst = cx.dst.join("aliases.js");
@@ -1047,6 +1178,22 @@ themePicker.onblur = handleThemeButtonsBlur;
                  &dst);
     }
 
+    // Update the full-text index. The previous index of this crate is removed even if no new one
+    // was built, since it may not match the search index anymore.
+    let dst = cx.dst.join("search-text-index.js");
+    let mut has_text_index = false;
+    if text_index.is_some() || dst.exists() {
+        let (mut all_indexes, _) = try_err!(collect(&dst, &krate.name, "textSearchIndex"), &dst);
+        all_indexes.extend(text_index);
+        all_indexes.sort();
+        has_text_index = !all_indexes.is_empty();
+        let mut w = try_err!(File::create(&dst), &dst);
+        try_err!(writeln!(&mut w, "var textSearchIndex = {{}};"), &dst);
+        for index in &all_indexes {
+            try_err!(writeln!(&mut w, "{}", index), &dst);
+        }
+    }
+
     // Update the search index
     let dst = cx.dst.join("search-index.js");
     let (mut all_indexes, mut krates) = try_err!(collect(&dst, &krate.name, "searchIndex"), &dst);
@@ -1062,6 +1209,8 @@ themePicker.onblur = handleThemeButtonsBlur;
                                        &[(minifier::js::Keyword::Null, "N")]),
                  &dst);
     }
+    // Tell the search whether there is a full-text index to load.
+    try_err!(writeln!(&mut w, "var hasTextSearchIndex = {};", has_text_index), &dst);
     try_err!(writeln!(&mut w, "initSearch(searchIndex);addSearchOptions(searchIndex);"), &dst);
 
     if options.enable_index_page {
@@ -1482,9 +1631,11 @@ impl DocFolder for Cache {
                             name: s.to_string(),
                             path: path.join("::"),
                             desc: plain_summary_line(item.doc_value()),
+                            doc: full_text_doc(self.full_text_search, &item),
                             parent,
                             parent_idx: None,
                             search_type: get_index_search_type(&item),
+                            badges: IndexItemBadges::new(&item),
                         });
                     }
                 }
@@ -1674,9 +1825,11 @@ impl<'a> Cache {
                                 name: item_name.to_string(),
                                 path: path.clone(),
                                 desc: plain_summary_line(item.doc_value()),
+                                doc: None,
                                 parent: None,
                                 parent_idx: None,
                                 search_type: get_index_search_type(&item),
+                                badges: IndexItemBadges::new(&item),
                             });
             }
         }
@@ -2097,6 +2250,9 @@ impl Context {
                     try_err!(this.shared.ensure_dir(&this.dst), &this.dst);
                     let joint_dst = this.dst.join("index.html");
                     try_err!(fs::write(&joint_dst, buf), &joint_dst);
+                    this.shared.finish_page(Some(&joint_dst));
+                } else {
+                    this.shared.finish_page(None);
                 }
 
                 let m = match item.inner {
@@ -2131,6 +2287,7 @@ impl Context {
                 try_err!(self.shared.ensure_dir(&self.dst), &self.dst);
                 let joint_dst = self.dst.join(file_name);
                 try_err!(fs::write(&joint_dst, buf), &joint_dst);
+                self.shared.finish_page(Some(&joint_dst));
 
                 if !self.render_redirect_pages {
                     all.append(full_path(self, &item), &item_type);
@@ -2155,6 +2312,8 @@ impl Context {
                     let mut redirect_out = BufWriter::new(redirect_out);
                     try_err!(layout::redirect(&mut redirect_out, file_name), &redir_dst);
                 }
+            } else {
+                self.shared.finish_page(None);
             }
         }
         Ok(())
@@ -2391,6 +2550,15 @@ fn plain_summary_line(s: Option<&str>) -> String {
     markdown::plain_summary_line(&line[..])
 }
 
+/// The documentation of `item` kept in the search index for the full-text index, if there is one.
+fn full_text_doc(full_text_search: bool, item: &clean::Item) -> Option<String> {
+    if full_text_search {
+        Some(item.doc_value().unwrap_or("").to_string())
+    } else {
+        None
+    }
+}
+
 fn document(w: &mut fmt::Formatter, cx: &Context, item: &clean::Item) -> fmt::Result {
     if let Some(ref name) = item.name {
         info!("Documenting {}", name);
@@ -2439,6 +2607,7 @@ fn document_full(w: &mut fmt::Formatter, item: &clean::Item,
     if let Some(s) = cx.shared.maybe_collapsed_doc_value(item) {
         debug!("Doc block: =====\n{}\n=====", s);
         render_markdown(w, cx, &*s, item.links(), prefix, is_hidden)?;
+        cx.shared.add_doc_links(item, &*s);
     } else if !prefix.is_empty() {
         write!(w, "<div class='docblock{}'>{}</div>",
                if is_hidden { " hidden" } else { "" },
@@ -2460,6 +2629,27 @@ fn document_stability(w: &mut fmt::Formatter, cx: &Context, item: &clean::Item,
     Ok(())
 }
 
+/// Renders the calls to `item` found in the crates given with `--scrape-examples`.
+fn document_examples(w: &mut fmt::Formatter, item: &clean::Item) -> fmt::Result {
+    let cache = cache();
+    let examples = match cache.scraped_examples.get(&item.def_id) {
+        Some(examples) => examples,
+        None => return Ok(()),
+    };
+    write!(w, "<details class='scraped-examples'><summary>Examples found in repository</summary>")?;
+    for example in examples {
+        write!(w, "<div class='scraped-example'>\
+                   <div class='scraped-example-title'>{} (line {})</div>{}</div>",
+               Escape(&example.file),
+               example.line,
+               highlight::render_with_highlighting(&example.code,
+                                                   Some("rust-example-rendered"),
+                                                   None,
+                                                   None))?;
+    }
+    write!(w, "</details>")
+}
+
 fn document_non_exhaustive_header(item: &clean::Item) -> &str {
     if item.is_non_exhaustive() { " (Non-exhaustive)" } else { "" }
 }
@@ -2635,24 +2825,14 @@ fn item_module(w: &mut fmt::Formatter, cx: &Context,
             }
 
             clean::ImportItem(ref import) => {
-                write!(w, "<tr><td><code>{}{}</code></td></tr>",
-                       VisSpace(&myitem.visibility), *import)?;
+                write!(w, "<tr><td><code>{}{}</code></td><td class='docblock-short'>{}</td></tr>",
+                       VisSpace(&myitem.visibility), *import, stability_tags(myitem, cx))?;
             }
 
             _ => {
                 if myitem.name.is_none() { continue }
 
-                let stabilities = short_stability(myitem, cx, false);
-
-                let stab_docs = if !stabilities.is_empty() {
-                    stabilities.iter()
-                               .map(|s| format!("[{}]", s))
-                               .collect::<Vec<_>>()
-                               .as_slice()
-                               .join(" ")
-                } else {
-                    String::new()
-                };
+                let stab_docs = stability_tags(myitem, cx);
 
                 let unsafety_flag = match myitem.inner {
                     clean::FunctionItem(ref func) | clean::ForeignFunctionItem(ref func)
@@ -2705,6 +2885,15 @@ fn item_module(w: &mut fmt::Formatter, cx: &Context,
     Ok(())
 }
 
+/// Renders the short stability, deprecation and portability notes of `item` shown in module
+/// listings.
+fn stability_tags(item: &clean::Item, cx: &Context) -> String {
+    short_stability(item, cx, false).iter()
+        .map(|s| format!("[{}]", s))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 fn short_stability(item: &clean::Item, cx: &Context, show_reason: bool) -> Vec<String> {
     let mut stability = vec![];
     let error_codes = ErrorCodes::from(UnstableFeatures::from_environment().is_nightly_build());
@@ -2802,6 +2991,26 @@ fn short_stability(item: &clean::Item, cx: &Context, show_reason: bool) -> Vec<S
         }));
     }
 
+    if show_reason && !item.source.expansions.is_empty() {
+        let expansions = item.source.expansions.iter()
+            .map(|expansion| {
+                // Like the `[src]` links, make the path relative to the crate's source root.
+                let filename = match expansion.filename {
+                    FileName::Real(ref path) => {
+                        path.strip_prefix(&cx.shared.src_root).unwrap_or(path).display().to_string()
+                    }
+                    ref filename => filename.to_string(),
+                };
+                format!("<code>{}</code> at {}:{}",
+                        Escape(&expansion.macro_name),
+                        Escape(&filename),
+                        expansion.line)
+            })
+            .collect::<Vec<_>>();
+        stability.push(format!("<div class='stab macro-expansion'>Generated by {}</div>",
+                               expansions.join(", in the expansion of ")));
+    }
+
     stability
 }
 
@@ -2858,7 +3067,8 @@ fn item_function(w: &mut fmt::Formatter, cx: &Context, it: &clean::Item,
               name_len,
               indent: 0,
            })?;
-    document(w, cx, it)
+    document(w, cx, it)?;
+    document_examples(w, it)
 }
 
 fn render_implementor(cx: &Context, implementor: &Impl, w: &mut fmt::Formatter,
@@ -3022,6 +3232,7 @@ fn item_trait(
         render_stability_since(w, m, t)?;
         write!(w, "</span></h3>")?;
         document(w, cx, m)?;
+        document_examples(w, m)?;
         Ok(())
     }
 
@@ -4016,6 +4227,7 @@ fn render_impl(w: &mut fmt::Formatter, cx: &Context, i: &Impl, link: AssocItemLi
             let mut ids = cx.id_map.borrow_mut();
             write!(w, "<div class='docblock'>{}</div>",
                    Markdown(&*dox, &i.impl_item.links(), RefCell::new(&mut ids), cx.codes))?;
+            cx.shared.add_doc_links(&i.impl_item, &*dox);
         }
     }
 
@@ -4119,6 +4331,7 @@ fn render_impl(w: &mut fmt::Formatter, cx: &Context, i: &Impl, link: AssocItemLi
                     document_stability(w, cx, item, is_hidden)?;
                     if show_def_docs {
                         document_full(w, item, cx, "", is_hidden)?;
+                        document_examples(w, item)?;
                     }
                 }
             } else {
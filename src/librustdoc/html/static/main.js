@@ -68,6 +68,7 @@ if (!String.prototype.endsWith) {
     // 0 for "In Names"
     // 1 for "In Parameters"
     // 2 for "In Return Types"
+    // 3 for "In Documentation", when a full-text index was generated
     var currentTab = 0;
 
     var themesWidth = null;
@@ -415,8 +416,21 @@ if (!String.prototype.endsWith) {
 
     function initSearch(rawSearchIndex) {
         var currentResults, index, searchIndex;
+        // The position in `searchIndex` of the first item of each crate.
+        var crateOffsets = {};
+        // The full-text index, `undefined` until it is requested, then `null` until it is loaded
+        // or if there is none.
+        var textIndex;
+        // Which items the search filters hide from the results, read before each search.
+        var hiddenItems = {};
         var MAX_LEV_DISTANCE = 3;
         var MAX_RESULTS = 200;
+        // These must be kept in sync with `html/text_search.rs`.
+        var TEXT_SEARCH_STOP_WORDS = ["an", "and", "are", "as", "at", "be", "by", "for", "from",
+                                      "if", "in", "into", "is", "it", "of", "on", "or", "that",
+                                      "the", "this", "to", "was", "will", "with"];
+        var TEXT_SEARCH_SUFFIXES = [["sses", "ss"], ["ches", "ch"], ["shes", "sh"], ["xes", "x"],
+                                    ["ies", "y"], ["ing", ""], ["ed", ""], ["ly", ""], ["s", ""]];
         var GENERICS_DATA = 1;
         var NAME = 0;
         var INPUTS_DATA = 0;
@@ -468,7 +482,7 @@ if (!String.prototype.endsWith) {
                     if (results[i].id > -1) {
                         var obj = searchIndex[results[i].id];
                         obj.lev = results[i].lev;
-                        if (isType !== true || obj.type) {
+                        if ((isType !== true || obj.type) && !isFilteredOut(obj)) {
                             var res = buildHrefAndPath(obj);
                             obj.displayPath = pathSplitter(res[0]);
                             obj.fullPath = obj.displayPath + obj.name;
@@ -1041,15 +1055,48 @@ if (!String.prototype.endsWith) {
                 }
             }
 
+            var results_in_docs = {};
+            if (textIndex) {
+                var terms = textSearchTerms(valLower);
+                for (var crate in textIndex) {
+                    if (!textIndex.hasOwnProperty(crate) || !crateOffsets.hasOwnProperty(crate) ||
+                        (filterCrates !== undefined && crate !== filterCrates)) {
+                        continue;
+                    }
+                    var found = null;
+                    for (var i = 0; i < terms.length; ++i) {
+                        var items = textSearchItems(textIndex[crate], terms[i]);
+                        found = found === null ? items : found.filter(function(item) {
+                            return items.indexOf(item) !== -1;
+                        });
+                    }
+                    for (i = 0; found !== null && i < found.length; ++i) {
+                        var id = crateOffsets[crate] + found[i];
+                        if (searchIndex[id] &&
+                                typePassesFilter(typeFilter, searchIndex[id].ty)) {
+                            results_in_docs[id] = {
+                                id: id,
+                                index: -1,
+                                lev: 0,
+                            };
+                        }
+                    }
+                }
+            }
+
             var ret = {
                 'in_args': sortResults(results_in_args, true),
                 'returned': sortResults(results_returned, true),
                 'others': sortResults(results),
+                'in_docs': sortResults(results_in_docs),
             };
             if (ALIASES && ALIASES[window.currentCrate] &&
                     ALIASES[window.currentCrate][query.raw]) {
                 var aliases = ALIASES[window.currentCrate][query.raw];
                 for (var i = 0; i < aliases.length; ++i) {
+                    if (isFilteredOut(aliases[i])) {
+                        continue;
+                    }
                     aliases[i].is_alias = true;
                     aliases[i].alias = query.raw;
                     aliases[i].path = aliases[i].p;
@@ -1066,6 +1113,83 @@ if (!String.prototype.endsWith) {
             return ret;
         }
 
+        /**
+         * Splits `text` into the terms of the full-text index, the same way rustdoc does in
+         * `html/text_search.rs`.
+         */
+        function textSearchTerms(text) {
+            var terms = [];
+            var words = text.toLowerCase().split(/[^a-z0-9]+/);
+            for (var i = 0; i < words.length; ++i) {
+                if (words[i].length >= 2 && TEXT_SEARCH_STOP_WORDS.indexOf(words[i]) === -1) {
+                    terms.push(textSearchStem(words[i]));
+                }
+            }
+            return terms;
+        }
+
+        function textSearchStem(word) {
+            if (word.length <= 3) {
+                return word;
+            }
+            for (var i = 0; i < TEXT_SEARCH_SUFFIXES.length; ++i) {
+                var suffix = TEXT_SEARCH_SUFFIXES[i][0];
+                var stem = word.substring(0, word.length - suffix.length);
+                if (!word.endsWith(suffix) || stem.length < 3) {
+                    continue;
+                }
+                if (suffix === "s" && /(ss|us|is)$/.test(word)) {
+                    break;
+                }
+                word = stem + TEXT_SEARCH_SUFFIXES[i][1];
+                if ((suffix === "ing" || suffix === "ed") &&
+                    /([^aeiouylsz])\1$/.test(word)) {
+                    word = word.substring(0, word.length - 1);
+                }
+                break;
+            }
+            if (word.length > 3 && word.endsWith("e")) {
+                word = word.substring(0, word.length - 1);
+            }
+            return word;
+        }
+
+        /**
+         * Returns the positions of the items of a crate whose documentation contains `term`, out
+         * of the postings of the crate in the full-text index.
+         *
+         * The positions are stored as the difference with the previous one.
+         */
+        function textSearchItems(crateIndex, term) {
+            var items = [];
+            if (crateIndex.hasOwnProperty(term)) {
+                var position = 0;
+                var deltas = crateIndex[term];
+                for (var i = 0; i < deltas.length; ++i) {
+                    position += deltas[i];
+                    items.push(position);
+                }
+            }
+            return items;
+        }
+
+        function loadTextIndex() {
+            textIndex = null;
+            // `search-index.js` tells whether `search-text-index.js` was generated.
+            if (!window.hasTextSearchIndex) {
+                return;
+            }
+            var script = document.createElement('script');
+            script.src = window.rootPath + "search-text-index.js";
+            script.onload = function() {
+                if (window.textSearchIndex) {
+                    textIndex = window.textSearchIndex;
+                    search(undefined, true);
+                }
+            };
+            document.head.appendChild(script);
+        }
+
         /**
          * Validate performs the following boolean logic. For example:
          * "File::open" will give IF A PARENT EXISTS => ("file" && "open")
@@ -1164,7 +1288,7 @@ if (!String.prototype.endsWith) {
             search_input.onkeydown = function(e) {
                 // "actives" references the currently highlighted item in each search tab.
                 // Each array in "actives" represents a tab.
-                var actives = [[], [], []];
+                var actives = [[], [], [], []];
                 // "current" is used to know which tab we're looking into.
                 var current = 0;
                 onEach(document.getElementsByClassName('search-results'), function(e) {
@@ -1201,10 +1325,11 @@ if (!String.prototype.endsWith) {
                             actives[currentTab][0].getElementsByTagName('a')[0].href;
                     }
                 } else if (e.which === 9) { // tab
+                    var nbTabs = document.getElementById('titles').childNodes.length;
                     if (e.shiftKey) {
-                        printTab(currentTab > 0 ? currentTab - 1 : 2);
+                        printTab(currentTab > 0 ? currentTab - 1 : nbTabs - 1);
                     } else {
-                        printTab(currentTab > 1 ? 0 : currentTab + 1);
+                        printTab(currentTab < nbTabs - 1 ? currentTab + 1 : 0);
                     }
                     e.preventDefault();
                 } else if (e.which === 16) { // shift
@@ -1305,7 +1430,7 @@ if (!String.prototype.endsWith) {
                               item.displayPath + '<span class="' + type + '">' +
                               name + '</span></a></td><td>' +
                               '<a href="' + item.href + '">' +
-                              '<span class="desc">' + escape(item.desc) +
+                              '<span class="desc">' + makeBadges(item) + escape(item.desc) +
                               '&nbsp;</span></a></td></tr>';
                 });
                 output += '</table>';
@@ -1327,6 +1452,27 @@ if (!String.prototype.endsWith) {
             return [output, length];
         }
 
+        function makeBadges(item) {
+            var output = '';
+            if (item.deprecated === true) {
+                output += '<span class="stab deprecated">Deprecated</span>';
+            }
+            if (item.unstable === true) {
+                output += '<span class="stab unstable">Experimental</span>';
+            }
+            if (item.cfg !== undefined) {
+                // The description of the platforms is HTML written by rustdoc.
+                output += '<span class="stab portability">' + item.cfg + '</span>';
+            }
+            return output;
+        }
+
+        function makeFilter(setting, text) {
+            var checked = getCurrentValue(setting) === "true" ? ' checked' : '';
+            return '<label><input type="checkbox" data-setting="' + setting + '"' + checked +
+                   '> ' + text + '</label>';
+        }
+
         function makeTabHeader(tabNb, text, nbElems) {
             if (currentTab === tabNb) {
                 return '<div class="selected">' + text +
@@ -1352,6 +1498,10 @@ if (!String.prototype.endsWith) {
             var ret_others = addTab(results['others'], query);
             var ret_in_args = addTab(results['in_args'], query, false);
             var ret_returned = addTab(results['returned'], query, false);
+            var ret_in_docs = textIndex ? addTab(results['in_docs'], query, false) : null;
+            if (ret_in_docs === null && currentTab === 3) {
+                currentTab = 0;
+            }
 
             var filter = "";
             if (filterCrates !== undefined) {
@@ -1360,12 +1510,18 @@ if (!String.prototype.endsWith) {
 
             var output = '<h1>Results for ' + escape(query.query) +
                 (query.type ? ' (type: ' + escape(query.type) + ')' : '') + filter + '</h1>' +
+                '<div id="search-filters">' +
+                makeFilter("rustdoc-hide-deprecated", "Hide deprecated items") +
+                makeFilter("rustdoc-hide-platform-specific", "Hide platform-specific items") +
+                '</div>' +
                 '<div id="titles">' +
                 makeTabHeader(0, "In Names", ret_others[1]) +
                 makeTabHeader(1, "In Parameters", ret_in_args[1]) +
                 makeTabHeader(2, "In Return Types", ret_returned[1]) +
+                (ret_in_docs ? makeTabHeader(3, "In Documentation", ret_in_docs[1]) : '') +
                 '</div><div id="results">' +
-                ret_others[0] + ret_in_args[0] + ret_returned[0] + '</div>';
+                ret_others[0] + ret_in_args[0] + ret_returned[0] +
+                (ret_in_docs ? ret_in_docs[0] : '') + '</div>';
 
             addClass(document.getElementById('main'), 'hidden');
             var search = document.getElementById('search');
@@ -1381,10 +1537,17 @@ if (!String.prototype.endsWith) {
                 e.style.width = width + 'px';
             });
             initSearchNav();
+            onEach(document.getElementById('search-filters').getElementsByTagName('input'),
+                   function(e) {
+                e.onchange = onFilterChange;
+            });
             var elems = document.getElementById('titles').childNodes;
             elems[0].onclick = function() { printTab(0); };
             elems[1].onclick = function() { printTab(1); };
             elems[2].onclick = function() { printTab(2); };
+            if (ret_in_docs) {
+                elems[3].onclick = function() { printTab(3); };
+            }
             printTab(currentTab);
         }
 
@@ -1394,6 +1557,7 @@ if (!String.prototype.endsWith) {
                 'in_args': [],
                 'returned': [],
                 'others': [],
+                'in_docs': [],
             };
 
             for (var i = 0; i < queries.length; ++i) {
@@ -1404,6 +1568,7 @@ if (!String.prototype.endsWith) {
                     results['in_args'].push(tmp['in_args']);
                     results['returned'].push(tmp['returned']);
                     results['others'].push(tmp['others']);
+                    results['in_docs'].push(tmp['in_docs']);
                 }
             }
             if (queries.length > 1) {
@@ -1451,12 +1616,14 @@ if (!String.prototype.endsWith) {
                     'in_args': mergeArrays(results['in_args']),
                     'returned': mergeArrays(results['returned']),
                     'others': mergeArrays(results['others']),
+                    'in_docs': mergeArrays(results['in_docs']),
                 };
             } else {
                 return {
                     'in_args': results['in_args'][0],
                     'returned': results['returned'][0],
                     'others': results['others'][0],
+                    'in_docs': results['in_docs'][0],
                 };
             }
         }
@@ -1470,6 +1637,17 @@ if (!String.prototype.endsWith) {
             return undefined;
         }
 
+        // Whether the search filters hide `item` from the results.
+        function isFilteredOut(item) {
+            return (hiddenItems.deprecated === true && item.deprecated === true) ||
+                   (hiddenItems.platformSpecific === true && item.cfg !== undefined);
+        }
+
+        function onFilterChange() {
+            updateLocalStorage(this.getAttribute('data-setting'), this.checked ? "true" : "false");
+            search(undefined, true);
+        }
+
         function search(e, forced) {
             var params = getQueryStringParams();
             var query = getQuery(search_input.value.trim());
@@ -1501,6 +1679,14 @@ if (!String.prototype.endsWith) {
                 }
             }
 
+            if (textIndex === undefined) {
+                loadTextIndex();
+            }
+
+            hiddenItems = {
+                deprecated: getCurrentValue("rustdoc-hide-deprecated") === "true",
+                platformSpecific: getCurrentValue("rustdoc-hide-platform-specific") === "true",
+            };
             var filterCrates = getFilterCrates();
             showResults(execSearch(query, index, filterCrates), filterCrates);
         }
@@ -1520,6 +1706,7 @@ if (!String.prototype.endsWith) {
                     desc: rawSearchIndex[crate].doc,
                     type: null,
                 });
+                crateOffsets[crate] = searchIndex.length;
 
                 // an array of [(Number) item type,
                 //              (String) name,
@@ -1527,6 +1714,8 @@ if (!String.prototype.endsWith) {
                 //              (String) description,
                 //              (Number | null) the parent path index to `paths`]
                 //              (Object | null) the type of the function (if any)
+                //              (Number, optional) 1 if deprecated, plus 2 if unstable
+                //              (String, optional) the platforms the item is available on]
                 var items = rawSearchIndex[crate].items;
                 // an array of [(Number) item type,
                 //              (String) name]
@@ -1551,7 +1740,9 @@ if (!String.prototype.endsWith) {
                     var rawRow = items[i];
                     var row = {crate: crate, ty: rawRow[0], name: rawRow[1],
                                path: rawRow[2] || lastPath, desc: rawRow[3],
-                               parent: paths[rawRow[4]], type: rawRow[5]};
+                               parent: paths[rawRow[4]], type: rawRow[5],
+                               deprecated: (rawRow[6] & 1) !== 0, unstable: (rawRow[6] & 2) !== 0,
+                               cfg: rawRow[7]};
                     searchIndex.push(row);
                     if (typeof row.name === "string") {
                         var word = row.name.toLowerCase();
@@ -2217,7 +2408,7 @@ if (!String.prototype.endsWith) {
 
     // In the search display, allows to switch between tabs.
     function printTab(nb) {
-        if (nb === 0 || nb === 1 || nb === 2) {
+        if (nb === 0 || nb === 1 || nb === 2 || nb === 3) {
             currentTab = nb;
         }
         var nb_copy = nb;
@@ -0,0 +1,146 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Full-text index over the documentation of the items of a crate, written to
+//! `search-text-index.js` with `--enable-full-text-search`.
+//!
+//! The index of a crate maps each term found in the documentation to the positions of the items
+//! it was found in, among the items of the crate in `search-index.js`. Positions are sorted and
+//! stored as the difference with the previous one, which keeps the numbers small.
+//!
+//! Terms are lowercased words, without stop words, reduced by a small suffix-stripping stemmer.
+//! The search in `main.js` splits queries into terms the same way, so the two must be kept in
+//! sync.
+
+use std::collections::BTreeMap;
+
+use serialize::json::{as_json, Json, ToJson};
+
+use html::markdown;
+
+const STOP_WORDS: &[&str] = &["an", "and", "are", "as", "at", "be", "by", "for", "from", "if",
+                              "in", "into", "is", "it", "of", "on", "or", "that", "the", "this",
+                              "to", "was", "will", "with"];
+
+/// Suffixes removed by the stemmer, along with what they are replaced with. Only the first one
+/// that matches is removed.
+const SUFFIXES: &[(&str, &str)] = &[("sses", "ss"), ("ches", "ch"), ("shes", "sh"), ("xes", "x"),
+                                    ("ies", "y"), ("ing", ""), ("ed", ""), ("ly", ""), ("s", "")];
+
+/// Builds the line of `search-text-index.js` for `krate`, out of the documentation of the items
+/// of its search index, in the order of the search index.
+pub fn build_index<'a, I>(krate: &str, docs: I) -> String
+    where I: Iterator<Item = &'a str>
+{
+    let mut postings = BTreeMap::<String, Vec<usize>>::new();
+    for (position, doc) in docs.enumerate() {
+        for term in terms(&markdown::plain_text(doc)) {
+            let items = postings.entry(term).or_default();
+            if items.last() != Some(&position) {
+                items.push(position);
+            }
+        }
+    }
+
+    let index = postings.into_iter()
+        .map(|(term, items)| {
+            let mut last = 0;
+            let deltas = items.into_iter()
+                .map(|position| {
+                    let delta = position - last;
+                    last = position;
+                    delta
+                })
+                .collect::<Vec<_>>();
+            (term, deltas.to_json())
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    format!("textSearchIndex[{}] = {};", as_json(&krate), Json::Object(index))
+}
+
+/// Splits `text` into the terms of the index.
+fn terms<'a>(text: &'a str) -> impl Iterator<Item = String> + 'a {
+    text.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| word.len() >= 2)
+        .map(|word| word.to_ascii_lowercase())
+        .filter(|word| !STOP_WORDS.contains(&&**word))
+        .map(|word| stem(&word))
+}
+
+/// Reduces `word` to its stem, so that `match`, `matches` and `matched` are all found by any of
+/// them.
+fn stem(word: &str) -> String {
+    if word.len() <= 3 {
+        return word.to_string();
+    }
+
+    let mut word = word.to_string();
+    for &(suffix, replacement) in SUFFIXES {
+        if !word.ends_with(suffix) || word.len() - suffix.len() < 3 {
+            continue;
+        }
+        if suffix == "s" && (word.ends_with("ss") || word.ends_with("us") ||
+                             word.ends_with("is")) {
+            break;
+        }
+        let len = word.len() - suffix.len();
+        word.truncate(len);
+        word.push_str(replacement);
+        // `running` and `stopped` become `run` and `stop`.
+        if suffix == "ing" || suffix == "ed" {
+            let bytes = word.as_bytes();
+            let last = bytes[bytes.len() - 1];
+            if last == bytes[bytes.len() - 2] && !b"aeiouylsz".contains(&last) {
+                word.pop();
+            }
+        }
+        break;
+    }
+    if word.len() > 3 && word.ends_with('e') {
+        word.pop();
+    }
+    word
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_index, stem, terms};
+
+    #[test]
+    fn test_stem() {
+        for &(words, expected) in &[(&["match", "matches", "matched", "matching"][..], "match"),
+                                    (&["parse", "parses", "parsed", "parsing"][..], "pars"),
+                                    (&["type", "types"][..], "typ"),
+                                    (&["run", "running"][..], "run"),
+                                    (&["class", "classes"][..], "class"),
+                                    (&["entry", "entries"][..], "entry")] {
+            for word in words {
+                assert_eq!(stem(word), expected, "stem of {}", word);
+            }
+        }
+        assert_eq!(stem("status"), "status");
+        assert_eq!(stem("only"), "only");
+    }
+
+    #[test]
+    fn test_terms() {
+        let found = terms("Returns the entries of a `HashMap`, in order.").collect::<Vec<_>>();
+        assert_eq!(found, ["return", "entry", "hashmap", "order"]);
+    }
+
+    #[test]
+    fn test_build_index() {
+        let docs = ["Opens a file.", "No docs here", "Reads the whole file"];
+        assert_eq!(build_index("foo", docs.iter().cloned()),
+                   concat!(r#"textSearchIndex["foo"] = {"doc":[1],"fil":[0,2],"her":[1],"#,
+                           r#""no":[1],"open":[0],"read":[2],"whol":[2]};"#));
+    }
+}
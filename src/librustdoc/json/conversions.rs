@@ -0,0 +1,356 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Conversions of the types of the `clean` module into their JSON representation.
+//!
+//! Enums are written the way serde writes them by default: unit variants as a plain string,
+//! and every other variant as an object with a single key naming the variant.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use rustc::hir;
+use rustc::hir::def_id::DefId;
+use rustc::util::nodemap::FxHashMap;
+use serialize::json::{Json, ToJson};
+use syntax::ext::base::MacroKind;
+
+use clean;
+use doctree;
+
+/// Builds a JSON object out of `(key, value)` pairs.
+macro_rules! object {
+    ($($key:expr => $value:expr),* $(,)*) => ({
+        let mut map = BTreeMap::new();
+        $(map.insert($key.to_string(), $value.to_json());)*
+        Json::Object(map)
+    })
+}
+
+/// Builds the JSON form of a non-unit enum variant.
+pub fn variant(name: &str, value: Json) -> Json {
+    let mut map = BTreeMap::new();
+    map.insert(name.to_string(), value);
+    Json::Object(map)
+}
+
+thread_local!(static IDS: RefCell<FxHashMap<DefId, String>> = Default::default());
+
+/// Sets the ids of the items of the document being built, as assigned by `json::assign_ids`.
+pub fn set_ids(ids: FxHashMap<DefId, String>) {
+    IDS.with(|current| *current.borrow_mut() = ids);
+}
+
+/// The id of an item in the `index` and `paths` of the JSON output.
+///
+/// Ids are made of the kind and the canonical path of the item, so that two builds of the same
+/// API use the same ids. Items that rustdoc knows no path of, which are never part of the
+/// document, fall back to an id derived from their `DefId`.
+pub fn id(did: DefId) -> String {
+    IDS.with(|ids| ids.borrow().get(&did).cloned()).unwrap_or_else(|| {
+        format!("{}:{}", did.krate.as_u32(), did.index.as_raw_u32())
+    })
+}
+
+pub struct Id(pub DefId);
+
+impl ToJson for Id {
+    fn to_json(&self) -> Json {
+        Json::String(id(self.0))
+    }
+}
+
+impl ToJson for clean::Span {
+    fn to_json(&self) -> Json {
+        object! {
+            "filename" => self.filename.to_string(),
+            "begin" => (self.loline, self.locol),
+            "end" => (self.hiline, self.hicol),
+            "expansions" => self.expansions,
+        }
+    }
+}
+
+impl ToJson for clean::Expansion {
+    fn to_json(&self) -> Json {
+        object! {
+            "macro" => self.macro_name,
+            "filename" => self.filename.to_string(),
+            "line" => self.line,
+        }
+    }
+}
+
+impl ToJson for clean::Visibility {
+    fn to_json(&self) -> Json {
+        match *self {
+            clean::Public => "public".to_json(),
+            clean::Inherited => "default".to_json(),
+            clean::Visibility::Crate => "crate".to_json(),
+            clean::Visibility::Restricted(did, ref path) => variant("restricted", object! {
+                "parent" => Id(did),
+                "path" => path_to_string(path),
+            }),
+        }
+    }
+}
+
+impl ToJson for clean::Deprecation {
+    fn to_json(&self) -> Json {
+        object! {
+            "since" => non_empty(&self.since),
+            "note" => non_empty(&self.note),
+        }
+    }
+}
+
+impl ToJson for clean::Stability {
+    fn to_json(&self) -> Json {
+        let level = match self.level {
+            ::rustc::middle::stability::Stable => "stable",
+            ::rustc::middle::stability::Unstable => "unstable",
+        };
+        object! {
+            "level" => level,
+            "feature" => non_empty(&self.feature),
+            "since" => non_empty(&self.since),
+            "issue" => self.issue,
+        }
+    }
+}
+
+impl ToJson for clean::Generics {
+    fn to_json(&self) -> Json {
+        object! {
+            "params" => self.params,
+            "where_predicates" => self.where_predicates,
+        }
+    }
+}
+
+impl ToJson for clean::GenericParamDef {
+    fn to_json(&self) -> Json {
+        let kind = match self.kind {
+            clean::GenericParamDefKind::Lifetime => "lifetime".to_json(),
+            clean::GenericParamDefKind::Type { ref bounds, ref default, ref synthetic, .. } => {
+                variant("type", object! {
+                    "bounds" => bounds,
+                    "default" => default,
+                    "synthetic" => synthetic.is_some(),
+                })
+            }
+        };
+        object! {
+            "name" => self.name,
+            "kind" => kind,
+        }
+    }
+}
+
+impl ToJson for clean::WherePredicate {
+    fn to_json(&self) -> Json {
+        match *self {
+            clean::WherePredicate::BoundPredicate { ref ty, ref bounds } => {
+                variant("bound_predicate", object! {
+                    "type" => ty,
+                    "bounds" => bounds,
+                })
+            }
+            clean::WherePredicate::RegionPredicate { ref lifetime, ref bounds } => {
+                variant("region_predicate", object! {
+                    "lifetime" => lifetime,
+                    "bounds" => bounds,
+                })
+            }
+            clean::WherePredicate::EqPredicate { ref lhs, ref rhs } => {
+                variant("eq_predicate", object! {
+                    "lhs" => lhs,
+                    "rhs" => rhs,
+                })
+            }
+        }
+    }
+}
+
+impl ToJson for clean::GenericBound {
+    fn to_json(&self) -> Json {
+        match *self {
+            clean::GenericBound::TraitBound(ref poly_trait, modifier) => {
+                let modifier = match modifier {
+                    hir::TraitBoundModifier::None => "none",
+                    hir::TraitBoundModifier::Maybe => "maybe",
+                };
+                variant("trait_bound", object! {
+                    "trait" => poly_trait.trait_,
+                    "generic_params" => poly_trait.generic_params,
+                    "modifier" => modifier,
+                })
+            }
+            clean::GenericBound::Outlives(ref lifetime) => variant("outlives", lifetime.to_json()),
+        }
+    }
+}
+
+impl ToJson for clean::Lifetime {
+    fn to_json(&self) -> Json {
+        self.get_ref().to_json()
+    }
+}
+
+impl ToJson for clean::Type {
+    fn to_json(&self) -> Json {
+        match *self {
+            clean::ResolvedPath { ref path, ref typarams, did, is_generic } => {
+                variant("resolved_path", object! {
+                    "name" => path_to_string(path),
+                    "id" => Id(did),
+                    "args" => path.segments.last().map(|s| s.args.to_json()),
+                    "param_names" => typarams,
+                    "is_generic" => is_generic,
+                })
+            }
+            clean::Generic(ref name) => variant("generic", name.to_json()),
+            clean::Primitive(prim) => variant("primitive", prim.as_str().to_json()),
+            clean::BareFunction(ref decl) => variant("function_pointer", object! {
+                "is_unsafe" => decl.unsafety == hir::Unsafety::Unsafe,
+                "generic_params" => decl.generic_params,
+                "decl" => decl.decl,
+                "abi" => decl.abi.name(),
+            }),
+            clean::Tuple(ref types) => variant("tuple", types.to_json()),
+            clean::Slice(ref ty) => variant("slice", ty.to_json()),
+            clean::Array(ref ty, ref len) => variant("array", object! {
+                "type" => ty,
+                "len" => len,
+            }),
+            clean::Never => "never".to_json(),
+            // `Unique` is how rustdoc marks owned pointers internally, it is not a type of
+            // its own.
+            clean::Unique(ref ty) => ty.to_json(),
+            clean::RawPointer(mutability, ref ty) => variant("raw_pointer", object! {
+                "mutable" => mutability == clean::Mutable,
+                "type" => ty,
+            }),
+            clean::BorrowedRef { ref lifetime, mutability, ref type_ } => {
+                variant("borrowed_ref", object! {
+                    "lifetime" => lifetime,
+                    "mutable" => mutability == clean::Mutable,
+                    "type" => type_,
+                })
+            }
+            clean::QPath { ref name, ref self_type, ref trait_ } => {
+                variant("qualified_path", object! {
+                    "name" => name,
+                    "self_type" => self_type,
+                    "trait" => trait_,
+                })
+            }
+            clean::Infer => "infer".to_json(),
+            clean::ImplTrait(ref bounds) => variant("impl_trait", bounds.to_json()),
+        }
+    }
+}
+
+impl ToJson for clean::GenericArgs {
+    fn to_json(&self) -> Json {
+        match *self {
+            clean::GenericArgs::AngleBracketed { ref lifetimes, ref types, ref bindings } => {
+                variant("angle_bracketed", object! {
+                    "lifetimes" => lifetimes,
+                    "types" => types,
+                    "bindings" => bindings,
+                })
+            }
+            clean::GenericArgs::Parenthesized { ref inputs, ref output } => {
+                variant("parenthesized", object! {
+                    "inputs" => inputs,
+                    "output" => output,
+                })
+            }
+        }
+    }
+}
+
+impl ToJson for clean::TypeBinding {
+    fn to_json(&self) -> Json {
+        object! {
+            "name" => self.name,
+            "type" => self.ty,
+        }
+    }
+}
+
+impl ToJson for clean::FnDecl {
+    fn to_json(&self) -> Json {
+        let inputs = self.inputs.values.iter()
+            .map(|arg| (arg.name.clone(), arg.type_.to_json()))
+            .collect::<Vec<_>>();
+        let output = match self.output {
+            clean::Return(ref ty) => Some(ty.to_json()),
+            clean::DefaultReturn => None,
+        };
+        object! {
+            "inputs" => inputs,
+            "output" => output,
+            "variadic" => self.variadic,
+        }
+    }
+}
+
+impl ToJson for hir::FnHeader {
+    fn to_json(&self) -> Json {
+        object! {
+            "const" => self.constness == hir::Constness::Const,
+            "unsafe" => self.unsafety == hir::Unsafety::Unsafe,
+            "async" => self.asyncness == hir::IsAsync::Async,
+            "abi" => self.abi.name(),
+        }
+    }
+}
+
+impl ToJson for doctree::StructType {
+    fn to_json(&self) -> Json {
+        match *self {
+            doctree::Plain => "plain",
+            doctree::Tuple => "tuple",
+            doctree::Unit => "unit",
+        }.to_json()
+    }
+}
+
+impl ToJson for MacroKind {
+    fn to_json(&self) -> Json {
+        match *self {
+            MacroKind::Bang => "bang",
+            MacroKind::Attr => "attr",
+            MacroKind::Derive => "derive",
+            MacroKind::ProcMacroStub => "stub",
+        }.to_json()
+    }
+}
+
+/// Renders `path` as it would be written in source code.
+pub fn path_to_string(path: &clean::Path) -> String {
+    let mut s = String::new();
+    if path.global {
+        s.push_str("::");
+    }
+    for (i, segment) in path.segments.iter().enumerate() {
+        if i > 0 {
+            s.push_str("::");
+        }
+        s.push_str(&segment.name);
+    }
+    s
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    if s.is_empty() { None } else { Some(s.to_string()) }
+}
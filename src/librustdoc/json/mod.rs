@@ -0,0 +1,393 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Rustdoc's JSON backend
+//!
+//! This module writes the cleaned crate out as a single JSON file, `<crate>.json`, in the output
+//! directory, for consumption by tools rather than by people. The document has the following
+//! shape:
+//!
+//! ```json
+//! {
+//!     "format_version": 2,
+//!     "crate_name": "foo",
+//!     "crate_version": "0.1.0",
+//!     "root": "mod:foo",
+//!     "index": { "<id>": <item>, ... },
+//!     "paths": { "<id>": { "crate_id": 0, "path": ["foo", "Bar"], "kind": "struct" }, ... },
+//!     "external_crates": { "<crate_id>": { "name": "std", "html_root_url": "..." }, ... }
+//! }
+//! ```
+//!
+//! `index` holds every item documented in this crate, `paths` the full path of every item that
+//! can be referred to by id, including the items of other crates. Ids are made of the kind and
+//! the path of the item, like `struct:foo::Bar`, and are the same in every build of the same API.
+//!
+//! Any change to the shape of the document must bump `FORMAT_VERSION`.
+
+#[macro_use]
+mod conversions;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+
+use rustc::hir;
+use rustc::hir::def_id::DefId;
+use rustc::util::nodemap::FxHashMap;
+use serialize::json::{Json, ToJson};
+use syntax::print::pprust;
+
+use clean;
+use config::RenderOptions;
+use html::render::{Cache, Error, ExternalLocation, Impl, RenderInfo};
+
+use self::conversions::{variant, Id};
+
+/// The version of the JSON document written by this backend.
+pub const FORMAT_VERSION: u32 = 2;
+
+/// Writes the JSON form of `krate` into the output directory given in `options`.
+pub fn run(krate: clean::Crate,
+           options: RenderOptions,
+           renderinfo: RenderInfo) -> Result<(), Error> {
+    let dst = options.output;
+    let (krate, cache) = Cache::from_krate(renderinfo, &options.extern_html_root_urls,
+                                           &dst, false, krate);
+
+    let output = document(&krate, &cache);
+
+    fs::create_dir_all(&dst).map_err(|e| Error::new(e, &dst))?;
+    let path = dst.join(format!("{}.json", krate.name));
+    let mut w = BufWriter::new(File::create(&path).map_err(|e| Error::new(e, &path))?);
+    write!(w, "{}", output).map_err(|e| Error::new(e, &path))?;
+    w.flush().map_err(|e| Error::new(e, &path))
+}
+
+/// Builds the JSON document for `krate`, out of the cache built for it.
+pub fn document(krate: &clean::Crate, cache: &Cache) -> Json {
+    conversions::set_ids(assign_ids(krate, cache));
+
+    let mut index = BTreeMap::new();
+    if let Some(ref module) = krate.module {
+        add_item(module, cache, &mut index);
+    }
+    // Impls are pulled out of the crate while building the cache, so they have to be added to
+    // the index on their own.
+    for impls in cache.impls.values() {
+        for impl_ in impls {
+            add_item(&impl_.impl_item, cache, &mut index);
+        }
+    }
+
+    let paths = cache.paths.iter().chain(cache.external_paths.iter())
+        .map(|(&did, &(ref path, kind))| {
+            (conversions::id(did), object! {
+                "crate_id" => did.krate.as_u32(),
+                "path" => path,
+                "kind" => kind.css_class(),
+            })
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let external_crates = cache.extern_locations.iter()
+        .map(|(krate, &(ref name, _, ref location))| {
+            let html_root_url = match *location {
+                ExternalLocation::Remote(ref url) => Some(url.clone()),
+                ExternalLocation::Local | ExternalLocation::Unknown => None,
+            };
+            (krate.as_u32().to_string(), object! {
+                "name" => name,
+                "html_root_url" => html_root_url,
+            })
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    object! {
+        "format_version" => FORMAT_VERSION,
+        "crate_name" => krate.name,
+        "crate_version" => cache.crate_version,
+        "root" => krate.module.as_ref().map(|m| Id(m.def_id)),
+        "index" => index,
+        "paths" => paths,
+        "external_crates" => external_crates,
+    }
+}
+
+/// Assigns the ids of the items of `krate` and of the items it refers to.
+///
+/// An id is the kind of the item followed by its canonical path, like `struct:foo::Bar` or
+/// `method:foo::Bar::new`. Items without a name are given one: impls are named after their
+/// header, numbered in source order when a type has several impls with the same header, and
+/// imports after what they import.
+fn assign_ids(krate: &clean::Crate, cache: &Cache) -> FxHashMap<DefId, String> {
+    let mut ids = FxHashMap::default();
+    let mut paths = FxHashMap::default();
+    for (&did, &(ref path, _)) in cache.paths.iter().chain(cache.external_paths.iter()) {
+        paths.insert(did, path.join("::"));
+    }
+    for (&did, &(_, kind)) in cache.paths.iter().chain(cache.external_paths.iter()) {
+        ids.insert(did, format!("{}:{}", kind.css_class(), paths[&did]));
+    }
+
+    if let Some(ref module) = krate.module {
+        assign_item_ids(module, "", &paths, &mut ids);
+    }
+
+    let mut impls = cache.impls.values()
+        .flat_map(|impls| impls.iter())
+        .filter_map(|i| match i.impl_item.inner {
+            clean::ImplItem(ref impl_) => Some((format!("{:#}", impl_), &i.impl_item)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    impls.sort_by(|&(ref h1, i1), &(ref h2, i2)| {
+        (h1, &i1.source.filename, i1.source.loline, i1.source.locol)
+            .cmp(&(h2, &i2.source.filename, i2.source.loline, i2.source.locol))
+    });
+    let mut same_header = 0;
+    for (i, &(ref header, item)) in impls.iter().enumerate() {
+        if i > 0 && impls[i - 1].0 == *header {
+            same_header += 1;
+        } else {
+            same_header = 0;
+        }
+        let path = if same_header == 0 {
+            format!("<{}>", header)
+        } else {
+            format!("<{}>#{}", header, same_header + 1)
+        };
+        assign_item_ids(item, &path, &paths, &mut ids);
+    }
+    ids
+}
+
+/// Assigns the ids of `item`, a member of the item with the path `parent`, and of its children.
+fn assign_item_ids(item: &clean::Item,
+                   parent: &str,
+                   paths: &FxHashMap<DefId, String>,
+                   ids: &mut FxHashMap<DefId, String>) {
+    let path = match (paths.get(&item.def_id), &item.name, &item.inner) {
+        (Some(path), _, _) => path.clone(),
+        (None, _, &clean::ImplItem(_)) => parent.to_string(),
+        (None, &Some(ref name), _) if parent.is_empty() => name.clone(),
+        (None, &Some(ref name), _) => format!("{}::{}", parent, name),
+        (None, &None, &clean::ImportItem(ref import)) => {
+            let import = match *import {
+                clean::Import::Simple(ref name, ref source) => {
+                    format!("use {} as {}", conversions::path_to_string(&source.path), name)
+                }
+                clean::Import::Glob(ref source) => {
+                    format!("use {}::*", conversions::path_to_string(&source.path))
+                }
+            };
+            format!("{}::<{}>", parent, import)
+        }
+        (None, &None, _) => format!("{}::<{}>", parent, item.type_().css_class()),
+    };
+    ids.entry(item.def_id)
+       .or_insert_with(|| format!("{}:{}", item.type_().css_class(), path));
+
+    let children: &[clean::Item] = match item.inner {
+        clean::ModuleItem(ref m) => &m.items,
+        clean::StructItem(ref s) => &s.fields,
+        clean::UnionItem(ref u) => &u.fields,
+        clean::EnumItem(ref e) => &e.variants.raw,
+        clean::VariantItem(clean::Variant { kind: clean::VariantKind::Struct(ref s) }) => {
+            &s.fields
+        }
+        clean::TraitItem(ref t) => &t.items,
+        clean::ImplItem(ref i) => &i.items,
+        _ => &[],
+    };
+    for child in children {
+        assign_item_ids(child, &path, paths, ids);
+    }
+}
+
+/// Adds `item` and everything it contains to `index`.
+fn add_item(item: &clean::Item, cache: &Cache, index: &mut BTreeMap<String, Json>) {
+    if item.is_stripped() {
+        return;
+    }
+
+    let mut children = Vec::new();
+    let inner = item_inner(item, cache, &mut children);
+    index.insert(conversions::id(item.def_id), object! {
+        "id" => Id(item.def_id),
+        "crate_id" => item.def_id.krate.as_u32(),
+        "name" => item.name,
+        "kind" => item.type_().css_class(),
+        "visibility" => item.visibility,
+        "docs" => item.attrs.collapsed_doc_value(),
+        "attrs" => item.attrs.other_attrs.iter()
+            .map(|attr| pprust::attribute_to_string(attr))
+            .collect::<Vec<_>>(),
+        "deprecation" => item.deprecation,
+        "stability" => item.stability,
+        "span" => item.source,
+        "inner" => inner,
+    });
+
+    for child in children {
+        add_item(child, cache, index);
+    }
+}
+
+/// Returns the ids of `items` that are not stripped, and queues them to be added to the index.
+fn item_ids<'a>(items: &'a [clean::Item], children: &mut Vec<&'a clean::Item>) -> Vec<Id> {
+    items.iter()
+        .filter(|item| !item.is_stripped())
+        .map(|item| {
+            children.push(item);
+            Id(item.def_id)
+        })
+        .collect()
+}
+
+/// Returns the ids of the impls of the type or trait with the given id.
+fn impl_ids(impls: Option<&Vec<Impl>>) -> Vec<Id> {
+    impls.map_or(vec![], |impls| impls.iter().map(|i| Id(i.impl_item.def_id)).collect())
+}
+
+/// Returns the kind-specific part of the JSON form of `item`, adding the items it contains to
+/// `children`.
+fn item_inner<'a>(item: &'a clean::Item,
+                  cache: &Cache,
+                  children: &mut Vec<&'a clean::Item>) -> Json {
+    match item.inner {
+        clean::ModuleItem(ref m) => object! {
+            "is_crate" => m.is_crate,
+            "items" => item_ids(&m.items, children),
+        },
+        clean::ExternCrateItem(ref name, ref rename) => object! {
+            "name" => name,
+            "rename" => rename,
+        },
+        clean::ImportItem(ref import) => {
+            let (name, source, glob) = match *import {
+                clean::Import::Simple(ref name, ref source) => (Some(name), source, false),
+                clean::Import::Glob(ref source) => (None, source, true),
+            };
+            object! {
+                "source" => conversions::path_to_string(&source.path),
+                "name" => name.cloned(),
+                "id" => source.did.map(Id),
+                "glob" => glob,
+            }
+        }
+        clean::StructItem(ref s) => object! {
+            "struct_type" => s.struct_type,
+            "generics" => s.generics,
+            "fields_stripped" => s.fields_stripped,
+            "fields" => item_ids(&s.fields, children),
+            "impls" => impl_ids(cache.impls.get(&item.def_id)),
+        },
+        clean::UnionItem(ref u) => object! {
+            "struct_type" => u.struct_type,
+            "generics" => u.generics,
+            "fields_stripped" => u.fields_stripped,
+            "fields" => item_ids(&u.fields, children),
+            "impls" => impl_ids(cache.impls.get(&item.def_id)),
+        },
+        clean::StructFieldItem(ref ty) => ty.to_json(),
+        clean::EnumItem(ref e) => object! {
+            "generics" => e.generics,
+            "variants_stripped" => e.variants_stripped,
+            "variants" => item_ids(&e.variants.raw, children),
+            "impls" => impl_ids(cache.impls.get(&item.def_id)),
+        },
+        clean::VariantItem(ref v) => match v.kind {
+            clean::VariantKind::CLike => "plain".to_json(),
+            clean::VariantKind::Tuple(ref types) => variant("tuple", types.to_json()),
+            clean::VariantKind::Struct(ref s) => variant("struct", object! {
+                "fields_stripped" => s.fields_stripped,
+                "fields" => item_ids(&s.fields, children),
+            }),
+        },
+        clean::FunctionItem(ref f) | clean::ForeignFunctionItem(ref f) => object! {
+            "decl" => f.decl,
+            "generics" => f.generics,
+            "header" => f.header,
+        },
+        clean::MethodItem(ref m) => object! {
+            "decl" => m.decl,
+            "generics" => m.generics,
+            "header" => m.header,
+            "has_body" => true,
+        },
+        clean::TyMethodItem(ref m) => object! {
+            "decl" => m.decl,
+            "generics" => m.generics,
+            "header" => m.header,
+            "has_body" => false,
+        },
+        clean::TraitItem(ref t) => object! {
+            "is_auto" => t.is_auto,
+            "is_unsafe" => t.unsafety == hir::Unsafety::Unsafe,
+            "generics" => t.generics,
+            "bounds" => t.bounds,
+            "items" => item_ids(&t.items, children),
+            "implementors" => impl_ids(cache.implementors.get(&item.def_id)),
+        },
+        clean::ImplItem(ref i) => object! {
+            "is_unsafe" => i.unsafety == hir::Unsafety::Unsafe,
+            "generics" => i.generics,
+            "provided_trait_methods" => i.provided_trait_methods.iter()
+                .cloned()
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            "trait" => i.trait_,
+            "for" => i.for_,
+            "items" => item_ids(&i.items, children),
+            "negative" => i.polarity == Some(clean::ImplPolarity::Negative),
+            "synthetic" => i.synthetic,
+            "blanket_impl" => i.blanket_impl,
+        },
+        clean::TypedefItem(ref t, _) => object! {
+            "type" => t.type_,
+            "generics" => t.generics,
+        },
+        clean::ExistentialItem(ref e, _) => object! {
+            "bounds" => e.bounds,
+            "generics" => e.generics,
+        },
+        clean::StaticItem(ref s) | clean::ForeignStaticItem(ref s) => object! {
+            "type" => s.type_,
+            "mutable" => s.mutability == clean::Mutable,
+            "expr" => s.expr,
+        },
+        clean::ConstantItem(ref c) => object! {
+            "type" => c.type_,
+            "expr" => c.expr,
+        },
+        clean::AssociatedConstItem(ref ty, ref default) => object! {
+            "type" => ty,
+            "default" => default,
+        },
+        clean::AssociatedTypeItem(ref bounds, ref default) => object! {
+            "bounds" => bounds,
+            "default" => default,
+        },
+        clean::MacroItem(ref m) => m.source.to_json(),
+        clean::ProcMacroItem(ref m) => object! {
+            "kind" => m.kind,
+            "helpers" => m.helpers,
+        },
+        clean::PrimitiveItem(prim) => object! {
+            "name" => prim.as_str(),
+            "impls" => impl_ids(cache.impls.get(&item.def_id)),
+        },
+        clean::KeywordItem(ref keyword) => keyword.to_json(),
+        clean::ForeignTypeItem => Json::Null,
+        clean::StrippedItem(..) => unreachable!(),
+    }
+}
@@ -65,24 +65,30 @@ use rustc::session::config::{ErrorOutputType, RustcOptGroup};
 #[macro_use]
 mod externalfiles;
 
+mod api_diff;
 mod clean;
 mod config;
 mod core;
+mod coverage;
 mod doctree;
 mod fold;
+mod json;
 pub mod html {
     crate mod highlight;
     crate mod escape;
     crate mod item_type;
     crate mod format;
     crate mod layout;
+    crate mod link_check;
     pub mod markdown;
     crate mod render;
     crate mod static_files;
+    crate mod text_search;
     crate mod toc;
 }
 mod markdown;
 mod passes;
+mod scrape_examples;
 mod visit_ast;
 mod visit_lib;
 mod test;
@@ -142,7 +148,7 @@ fn opts() -> Vec<RustcOptGroup> {
                      "[rust]")
         }),
         stable("w", |o| {
-            o.optopt("w", "output-format", "the output type to write", "[html]")
+            o.optopt("w", "output-format", "the output type to write", "[html|json]")
         }),
         stable("o", |o| o.optopt("o", "output", "where to place the output", "PATH")),
         stable("crate-name", |o| {
@@ -253,6 +259,11 @@ fn opts() -> Vec<RustcOptGroup> {
         unstable("display-warnings", |o| {
             o.optflag("", "display-warnings", "to print code warnings when testing doc")
         }),
+        unstable("merge-doctests", |o| {
+            o.optflag("",
+                      "merge-doctests",
+                      "compile the doctests that allow it into a single binary when testing doc")
+        }),
         unstable("crate-version", |o| {
             o.optopt("", "crate-version", "crate version to print into documentation", "VERSION")
         }),
@@ -338,6 +349,44 @@ fn opts() -> Vec<RustcOptGroup> {
                        "enable-index-page",
                        "To enable generation of the index page")
         }),
+        unstable("api-diff", |o| {
+            o.optopt("",
+                     "api-diff",
+                     "compare the public API of the crate against the JSON documentation of \
+                      an older version of it, and print the changes instead of documenting it",
+                     "PATH")
+        }),
+        unstable("api-diff-format", |o| {
+            o.optopt("",
+                     "api-diff-format",
+                     "the format of the changes printed by --api-diff",
+                     "[text|json]")
+        }),
+        unstable("show-coverage", |o| {
+            o.optflag("",
+                      "show-coverage",
+                      "calculate the percentage of public items with documentation and code \
+                       examples, and print it instead of documenting the crate")
+        }),
+        unstable("scrape-examples", |o| {
+            o.optmulti("",
+                       "scrape-examples",
+                       "search the crate rooted at PATH for calls to the documented functions \
+                        and methods, and show them in their documentation",
+                       "PATH")
+        }),
+        unstable("enable-full-text-search", |o| {
+            o.optflag("",
+                      "enable-full-text-search",
+                      "write an index of the words of the documentation, to search for items \
+                       by their documentation")
+        }),
+        unstable("check-links", |o| {
+            o.optflag("",
+                      "check-links",
+                      "warn about relative links and anchors of the documentation that point \
+                       nowhere in the rendered pages")
+        }),
     ]
 }
 
@@ -387,13 +436,30 @@ fn main_args(args: &[String]) -> isize {
         info!("going to format");
         let (error_format, treat_err_as_bug, ui_testing) = diag_opts;
         let diag = core::new_handler(error_format, None, treat_err_as_bug, ui_testing);
-        match html::render::run(
-            krate,
-            renderopts,
-            passes.into_iter().collect(),
-            renderinfo,
-            &diag,
-        ) {
+        if renderopts.show_coverage {
+            coverage::run(&krate, renderopts.output_format);
+            return rustc_driver::EXIT_SUCCESS;
+        }
+        if renderopts.api_diff.is_some() {
+            return match api_diff::run(krate, renderopts, renderinfo) {
+                Ok(()) => rustc_driver::EXIT_SUCCESS,
+                Err(e) => {
+                    diag.struct_err(&e).emit();
+                    rustc_driver::EXIT_FAILURE
+                }
+            };
+        }
+        let res = match renderopts.output_format {
+            config::OutputFormat::Html => html::render::run(
+                krate,
+                renderopts,
+                passes.into_iter().collect(),
+                renderinfo,
+                &diag,
+            ),
+            config::OutputFormat::Json => json::run(krate, renderopts, renderinfo),
+        };
+        match res {
             Ok(_) => rustc_driver::EXIT_SUCCESS,
             Err(e) => {
                 diag.struct_err(&format!("couldn't generate documentation: {}", e.error))
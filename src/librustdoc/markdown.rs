@@ -148,6 +148,7 @@ pub fn test(mut options: Options, diag: &errors::Handler) -> isize {
     let mut opts = TestOptions::default();
     opts.no_crate_inject = true;
     opts.display_warnings = options.display_warnings;
+    opts.merge_doctests = options.merge_doctests;
     let mut collector = Collector::new(options.input.display().to_string(), options.cfgs,
                                        options.libs, options.codegen_options, options.externs,
                                        true, opts, options.maybe_sysroot, None,
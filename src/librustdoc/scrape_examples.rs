@@ -0,0 +1,379 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Usage examples scraped from the crates given with `--scrape-examples`.
+//!
+//! The example crates are parsed but not compiled, so calls are matched without type information:
+//! path calls like `foo::bar()` or `Bar::new()` are resolved through the `use` items of the
+//! example, and method calls like `x.baz()` are only matched when no other documented method of
+//! the crate is called `baz`. Calls inside macro invocations are found as long as the macro takes
+//! comma-separated expressions, like `println!` or `assert_eq!` do.
+
+use std::cmp;
+use std::path::PathBuf;
+
+use errors;
+use rustc::hir::def_id::DefId;
+use rustc::util::nodemap::FxHashMap;
+use syntax::ast;
+use syntax::parse::{self, token, ParseSess};
+use syntax::source_map::{FilePathMapping, SourceMap};
+use syntax::symbol::keywords;
+use syntax::visit::{self, Visitor};
+use syntax_pos::Span;
+
+use clean;
+use html::item_type::ItemType;
+use html::render::Cache;
+
+/// Functions longer than this are cut down to the lines around the call.
+const MAX_SNIPPET_LINES: usize = 20;
+/// The number of lines kept before and after the call when a function is cut down.
+const CONTEXT_LINES: usize = 5;
+
+/// A call to a documented item, along with the code around it.
+#[derive(Clone, Debug)]
+pub struct Example {
+    /// The file the call was found in.
+    pub file: String,
+    /// The line of the call, starting at 1.
+    pub line: usize,
+    /// The function containing the call, or the lines around the call if the function is long.
+    pub code: String,
+}
+
+/// Searches the crates whose root files are given in `files` for calls to the functions and
+/// methods of `krate`, and returns the examples found for each of them.
+pub fn scrape(files: &[PathBuf],
+              krate: &str,
+              cache: &Cache,
+              diag: &errors::Handler) -> FxHashMap<DefId, Vec<Example>> {
+    let targets = Targets::new(cache);
+    let mut examples = FxHashMap::<DefId, Vec<Example>>::default();
+
+    for file in files {
+        let sess = ParseSess::new(FilePathMapping::empty());
+        let ast = match parse::parse_crate_from_file(file, &sess) {
+            Ok(ast) => ast,
+            Err(mut err) => {
+                err.emit();
+                diag.struct_warn(&format!("could not scrape examples from `{}`", file.display()))
+                    .emit();
+                continue;
+            }
+        };
+
+        let mut imports = Imports::new(krate);
+        visit::walk_crate(&mut imports, &ast);
+
+        let mut finder = CallFinder {
+            sess: &sess,
+            targets: &targets,
+            imports: &imports,
+            functions: Vec::new(),
+            calls: Vec::new(),
+        };
+        visit::walk_crate(&mut finder, &ast);
+
+        for (did, call, function) in finder.calls {
+            let example = make_example(sess.source_map(), call, function);
+            let found = examples.entry(did).or_default();
+            // Several calls in the same function only need to be shown once.
+            if !found.iter().any(|e| e.file == example.file && e.code == example.code) {
+                found.push(example);
+            }
+        }
+    }
+
+    examples
+}
+
+/// The functions and methods of the crate that calls are looked for.
+#[derive(Default)]
+struct Targets {
+    /// Functions and associated functions by their full path.
+    by_path: FxHashMap<Vec<String>, DefId>,
+    /// Methods by their name.
+    methods: FxHashMap<String, Vec<DefId>>,
+}
+
+impl Targets {
+    fn new(cache: &Cache) -> Targets {
+        let mut targets = Targets::default();
+        for (&did, &(ref path, kind)) in &cache.paths {
+            if !did.is_local() {
+                continue;
+            }
+            match kind {
+                ItemType::Function => {
+                    targets.by_path.insert(path.clone(), did);
+                }
+                ItemType::Trait => if let Some(trait_) = cache.traits.get(&did) {
+                    targets.add_assoc_items(path, &trait_.items);
+                },
+                _ => {}
+            }
+        }
+        // The methods of trait impls are documented on their trait, only inherent impls are
+        // looked at.
+        for (did, impls) in &cache.impls {
+            let path = match cache.paths.get(did) {
+                Some(&(ref path, _)) if did.is_local() => path,
+                _ => continue,
+            };
+            for impl_ in impls {
+                let inner = impl_.inner_impl();
+                if inner.trait_.is_none() {
+                    targets.add_assoc_items(path, &inner.items);
+                }
+            }
+        }
+        targets
+    }
+
+    fn add_assoc_items(&mut self, parent: &[String], items: &[clean::Item]) {
+        for item in items {
+            let name = match item.name {
+                Some(ref name) => name,
+                None => continue,
+            };
+            let has_self = match item.inner {
+                clean::MethodItem(ref m) => m.decl.self_type().is_some(),
+                clean::TyMethodItem(ref m) => m.decl.self_type().is_some(),
+                _ => continue,
+            };
+            let mut path = parent.to_vec();
+            path.push(name.clone());
+            self.by_path.insert(path, item.def_id);
+            if has_self {
+                self.methods.entry(name.clone()).or_default().push(item.def_id);
+            }
+        }
+    }
+
+    /// Returns the method called `name`, if there is only one.
+    fn method(&self, name: &str) -> Option<DefId> {
+        match self.methods.get(name) {
+            Some(dids) if dids.len() == 1 => Some(dids[0]),
+            _ => None,
+        }
+    }
+}
+
+/// The names brought into scope by the `use` and `extern crate` items of an example crate.
+///
+/// Scopes are not tracked: an import anywhere in the crate applies to the whole crate.
+struct Imports {
+    krate: String,
+    names: FxHashMap<String, Vec<String>>,
+    globs: Vec<Vec<String>>,
+}
+
+impl Imports {
+    fn new(krate: &str) -> Imports {
+        Imports {
+            krate: krate.to_string(),
+            names: FxHashMap::default(),
+            globs: Vec::new(),
+        }
+    }
+
+    fn add_use_tree(&mut self, tree: &ast::UseTree, prefix: &[String]) {
+        let mut path = prefix.to_vec();
+        path.extend(path_segments(&tree.prefix));
+        match tree.kind {
+            ast::UseTreeKind::Simple(rename, ..) => {
+                // `use foo::bar::{self}` imports `bar`.
+                if path.last().map_or(false, |s| s == "self") {
+                    path.pop();
+                }
+                let name = match (rename, path.last()) {
+                    (Some(rename), _) => rename.to_string(),
+                    (None, Some(name)) => name.clone(),
+                    (None, None) => return,
+                };
+                self.names.insert(name, path);
+            }
+            ast::UseTreeKind::Nested(ref trees) => {
+                for &(ref tree, _) in trees {
+                    self.add_use_tree(tree, &path);
+                }
+            }
+            ast::UseTreeKind::Glob => self.globs.push(path),
+        }
+    }
+
+    /// Returns the full paths `path` may refer to.
+    fn resolve(&self, path: &[String]) -> Vec<Vec<String>> {
+        let first = match path.first() {
+            Some(first) => first,
+            None => return vec![],
+        };
+        if *first == self.krate {
+            return vec![path.to_vec()];
+        }
+        if let Some(import) = self.names.get(first) {
+            let mut full = import.clone();
+            full.extend_from_slice(&path[1..]);
+            return vec![full];
+        }
+        self.globs.iter().map(|glob| {
+            let mut full = glob.clone();
+            full.extend_from_slice(path);
+            full
+        }).collect()
+    }
+}
+
+impl<'a> Visitor<'a> for Imports {
+    fn visit_item(&mut self, item: &'a ast::Item) {
+        match item.node {
+            ast::ItemKind::Use(ref tree) => self.add_use_tree(tree, &[]),
+            ast::ItemKind::ExternCrate(orig_name) => {
+                let name = orig_name.unwrap_or(item.ident.name);
+                if name.as_str() == &*self.krate && item.ident.name != name {
+                    self.names.insert(item.ident.to_string(), vec![self.krate.clone()]);
+                }
+            }
+            _ => {}
+        }
+        visit::walk_item(self, item);
+    }
+
+    fn visit_mac(&mut self, _mac: &'a ast::Mac) {
+        // Imports written by macros are not looked at.
+    }
+}
+
+struct CallFinder<'a> {
+    sess: &'a ParseSess,
+    targets: &'a Targets,
+    imports: &'a Imports,
+    /// The spans of the functions around the expression being visited, innermost last.
+    functions: Vec<Span>,
+    /// The calls found so far, along with the function they were found in.
+    calls: Vec<(DefId, Span, Option<Span>)>,
+}
+
+impl<'a> CallFinder<'a> {
+    fn add_call(&mut self, did: DefId, span: Span) {
+        let function = self.functions.last().cloned();
+        self.calls.push((did, span, function));
+    }
+}
+
+impl<'a> Visitor<'a> for CallFinder<'a> {
+    fn visit_item(&mut self, item: &'a ast::Item) {
+        let is_fn = match item.node {
+            ast::ItemKind::Fn(..) => true,
+            _ => false,
+        };
+        if is_fn {
+            self.functions.push(item.span);
+        }
+        visit::walk_item(self, item);
+        if is_fn {
+            self.functions.pop();
+        }
+    }
+
+    fn visit_impl_item(&mut self, item: &'a ast::ImplItem) {
+        self.functions.push(item.span);
+        visit::walk_impl_item(self, item);
+        self.functions.pop();
+    }
+
+    fn visit_trait_item(&mut self, item: &'a ast::TraitItem) {
+        self.functions.push(item.span);
+        visit::walk_trait_item(self, item);
+        self.functions.pop();
+    }
+
+    fn visit_expr(&mut self, expr: &'a ast::Expr) {
+        match expr.node {
+            ast::ExprKind::Call(ref callee, _) => {
+                if let ast::ExprKind::Path(None, ref path) = callee.node {
+                    let segments = path_segments(path).collect::<Vec<_>>();
+                    let did = self.imports.resolve(&segments).iter()
+                        .filter_map(|full| self.targets.by_path.get(full))
+                        .next()
+                        .cloned();
+                    if let Some(did) = did {
+                        self.add_call(did, expr.span);
+                    }
+                }
+            }
+            ast::ExprKind::MethodCall(ref segment, _) => {
+                if let Some(did) = self.targets.method(&segment.ident.as_str()) {
+                    self.add_call(did, expr.span);
+                }
+            }
+            _ => {}
+        }
+        visit::walk_expr(self, expr);
+    }
+
+    fn visit_mac(&mut self, mac: &'a ast::Mac) {
+        // Parse the arguments of the macro as a list of expressions, and give up on the first
+        // one that is not.
+        let mut parser = parse::stream_to_parser(self.sess, mac.node.stream());
+        while parser.token != token::Eof {
+            match parser.parse_expr() {
+                Ok(expr) => self.visit_expr(&expr),
+                Err(mut err) => {
+                    err.cancel();
+                    break;
+                }
+            }
+            if !parser.eat(&token::Comma) {
+                break;
+            }
+        }
+    }
+}
+
+/// Returns the names of the segments of `path`, leaving out the leading `::` of global paths.
+fn path_segments<'a>(path: &'a ast::Path) -> impl Iterator<Item = String> + 'a {
+    path.segments.iter()
+        .filter(|segment| segment.ident.name != keywords::PathRoot.name())
+        .map(|segment| segment.ident.to_string())
+}
+
+fn make_example(source_map: &SourceMap, call: Span, function: Option<Span>) -> Example {
+    let call_loc = source_map.lookup_char_pos(call.lo());
+    let (mut first, mut last) = match function {
+        Some(span) => (source_map.lookup_char_pos(span.lo()).line,
+                       source_map.lookup_char_pos(span.hi()).line),
+        None => (call_loc.line, source_map.lookup_char_pos(call.hi()).line),
+    };
+    if last - first >= MAX_SNIPPET_LINES {
+        first = cmp::max(first, call_loc.line.saturating_sub(CONTEXT_LINES));
+        last = cmp::min(last, call_loc.line + CONTEXT_LINES);
+    }
+
+    let lines = (first..last + 1)
+        .filter_map(|line| call_loc.file.get_line(line - 1))
+        .collect::<Vec<_>>();
+    let indent = lines.iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_left().len())
+        .min()
+        .unwrap_or(0);
+    let code = lines.iter()
+        .map(|line| if line.len() >= indent { &line[indent..] } else { "" })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Example {
+        file: call_loc.file.name.to_string(),
+        line: call_loc.line,
+        code,
+    }
+}
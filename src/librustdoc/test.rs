@@ -28,14 +28,14 @@ use syntax::edition::Edition;
 use syntax::feature_gate::UnstableFeatures;
 use syntax::with_globals;
 use syntax_pos::{BytePos, DUMMY_SP, Pos, Span, FileName};
-use tempfile::Builder as TempFileBuilder;
+use tempfile::{Builder as TempFileBuilder, TempDir};
 use testing;
 
 use std::env;
 use std::ffi::OsString;
 use std::io::prelude::*;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::panic::{self, AssertUnwindSafe};
 use std::process::Command;
 use std::str;
@@ -54,6 +54,8 @@ pub struct TestOptions {
     pub display_warnings: bool,
     /// Additional crate-level attributes to add to doctests.
     pub attrs: Vec<String>,
+    /// Whether to compile the doctests that allow it into a single test harness per edition.
+    pub merge_doctests: bool,
 }
 
 pub fn run(mut options: Options) -> isize {
@@ -115,6 +117,7 @@ pub fn run(mut options: Options) -> isize {
         });
         let mut opts = scrape_test_config(hir_forest.krate());
         opts.display_warnings |= options.display_warnings;
+        opts.merge_doctests = options.merge_doctests;
         let mut collector = Collector::new(
             crate_name,
             options.cfgs,
@@ -161,6 +164,7 @@ fn scrape_test_config(krate: &::rustc::hir::Crate) -> TestOptions {
         no_crate_inject: false,
         display_warnings: false,
         attrs: Vec::new(),
+        merge_doctests: false,
     };
 
     let test_attrs: Vec<_> = krate.attrs.iter()
@@ -190,8 +194,16 @@ fn run_test(test: &str, cratename: &str, filename: &FileName, line: usize,
             cfgs: Vec<String>, libs: Vec<SearchPath>,
             cg: CodegenOptions, externs: Externs,
             should_panic: bool, no_run: bool, as_test_harness: bool,
-            compile_fail: bool, mut error_codes: Vec<String>, opts: &TestOptions,
+            compile_fail: bool, ignore: bool, mut error_codes: Vec<String>,
+            check_output: bool, expected_output: Option<String>, opts: &TestOptions,
             maybe_sysroot: Option<PathBuf>, linker: Option<PathBuf>, edition: Edition) {
+    if check_output && expected_output.is_none() {
+        panic!("test isn't followed by a `text` code block with its expected output");
+    }
+    if check_output && (no_run || compile_fail || ignore) {
+        panic!("`check_output` can't be combined with `no_run`, `compile_fail` or `ignore`");
+    }
+
     // The test harness wants its own `main` and top-level functions, so
     // never wrap the test in `fn main() { ... }`.
     let (test, line_offset) = make_test(test, Some(cratename), as_test_harness, opts);
@@ -206,27 +218,6 @@ fn run_test(test: &str, cratename: &str, filename: &FileName, line: usize,
         name: FileName::DocTest(path, line as isize - line_offset as isize),
         input: test,
     };
-    let outputs = OutputTypes::new(&[(OutputType::Exe, None)]);
-
-    let sessopts = config::Options {
-        maybe_sysroot: maybe_sysroot.or_else(
-            || Some(env::current_exe().unwrap().parent().unwrap().parent().unwrap().to_path_buf())),
-        search_paths: libs,
-        crate_types: vec![config::CrateType::Executable],
-        output_types: outputs,
-        externs,
-        cg: config::CodegenOptions {
-            linker,
-            ..cg
-        },
-        test: as_test_harness,
-        unstable_features: UnstableFeatures::from_environment(),
-        debugging_opts: config::DebuggingOptions {
-            ..config::basic_debugging_options()
-        },
-        edition,
-        ..config::Options::default()
-    };
 
     // Shuffle around a few input and output handles here. We're going to pass
     // an explicit handle into rustc to collect output messages, but we also
@@ -238,13 +229,6 @@ fn run_test(test: &str, cratename: &str, filename: &FileName, line: usize,
     //
     // The basic idea is to not use a default Handler for rustc, and then also
     // not print things by default to the actual stderr.
-    struct Sink(Arc<Mutex<Vec<u8>>>);
-    impl Write for Sink {
-        fn write(&mut self, data: &[u8]) -> io::Result<usize> {
-            Write::write(&mut *self.0.lock().unwrap(), data)
-        }
-        fn flush(&mut self) -> io::Result<()> { Ok(()) }
-    }
     struct Bomb(Arc<Mutex<Vec<u8>>>, Box<dyn Write+Send>);
     impl Drop for Bomb {
         fn drop(&mut self) {
@@ -256,6 +240,77 @@ fn run_test(test: &str, cratename: &str, filename: &FileName, line: usize,
     let old = io::set_panic(Some(box Sink(data.clone())));
     let _bomb = Bomb(data.clone(), old.unwrap_or(box io::stdout()));
 
+    let (libdir, outdir, compile_result) = compile_test(input, cfgs, libs, cg, externs,
+                                                        as_test_harness, no_run, maybe_sysroot,
+                                                        linker, edition, &data);
+
+    match (compile_result, compile_fail) {
+        (Ok(()), true) => {
+            panic!("test compiled while it wasn't supposed to")
+        }
+        (Ok(()), false) => {}
+        (Err(()), true) => {
+            if error_codes.len() > 0 {
+                let out = String::from_utf8(data.lock().unwrap().to_vec()).unwrap();
+                error_codes.retain(|err| !out.contains(err));
+            }
+        }
+        (Err(()), false) => {
+            panic!("couldn't compile the test")
+        }
+    }
+
+    if error_codes.len() > 0 {
+        panic!("Some expected error codes were not found: {:?}", error_codes);
+    }
+
+    if no_run { return }
+
+    run_test_binary(&outdir.path().join("rust_out"), libdir, &[], should_panic,
+                    expected_output.as_ref().map(|s| &s[..]));
+}
+
+/// Writes the output of the compiler into a buffer shared with the test.
+struct Sink(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Sink {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        Write::write(&mut *self.0.lock().unwrap(), data)
+    }
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
+
+/// Compiles `input` into the executable `rust_out` of a new temporary directory, writing the
+/// diagnostics of the compiler into `data`. Returns the target library directory, the temporary
+/// directory and whether the compilation succeeded.
+fn compile_test(input: config::Input,
+                cfgs: Vec<String>, libs: Vec<SearchPath>,
+                cg: CodegenOptions, externs: Externs,
+                as_test_harness: bool, no_run: bool,
+                maybe_sysroot: Option<PathBuf>, linker: Option<PathBuf>, edition: Edition,
+                data: &Arc<Mutex<Vec<u8>>>) -> (PathBuf, TempDir, Result<(), ()>) {
+    let outputs = OutputTypes::new(&[(OutputType::Exe, None)]);
+
+    let sessopts = config::Options {
+        maybe_sysroot: maybe_sysroot.or_else(
+            || Some(env::current_exe().unwrap().parent().unwrap().parent().unwrap().to_path_buf())),
+        search_paths: libs,
+        crate_types: vec![config::CrateType::Executable],
+        output_types: outputs,
+        externs,
+        cg: config::CodegenOptions {
+            linker,
+            ..cg
+        },
+        test: as_test_harness,
+        unstable_features: UnstableFeatures::from_environment(),
+        debugging_opts: config::DebuggingOptions {
+            ..config::basic_debugging_options()
+        },
+        edition,
+        ..config::Options::default()
+    };
+
     let (libdir, outdir, compile_result) = driver::spawn_thread_pool(sessopts, |sessopts| {
         let source_map = Lrc::new(SourceMap::new(sessopts.file_path_mapping()));
         let emitter = errors::emitter::EmitterWriter::new(box Sink(data.clone()),
@@ -311,35 +366,21 @@ fn run_test(test: &str, cratename: &str, filename: &FileName, line: usize,
         (libdir, outdir, compile_result)
     });
 
-    match (compile_result, compile_fail) {
-        (Ok(()), true) => {
-            panic!("test compiled while it wasn't supposed to")
-        }
-        (Ok(()), false) => {}
-        (Err(()), true) => {
-            if error_codes.len() > 0 {
-                let out = String::from_utf8(data.lock().unwrap().to_vec()).unwrap();
-                error_codes.retain(|err| !out.contains(err));
-            }
-        }
-        (Err(()), false) => {
-            panic!("couldn't compile the test")
-        }
-    }
-
-    if error_codes.len() > 0 {
-        panic!("Some expected error codes were not found: {:?}", error_codes);
-    }
-
-    if no_run { return }
+    (libdir, outdir.into_inner().unwrap(), compile_result)
+}
 
+/// Runs a compiled test with the given arguments, panicking if its outcome isn't the expected
+/// one, or if it doesn't print `expected_output` when given.
+fn run_test_binary(binary: &Path, libdir: PathBuf, args: &[&str], should_panic: bool,
+                   expected_output: Option<&str>) {
     // Run the code!
     //
     // We're careful to prepend the *target* dylib search path to the child's
     // environment to ensure that the target loads the right libraries at
     // runtime. It would be a sad day if the *host* libraries were loaded as a
     // mistake.
-    let mut cmd = Command::new(&outdir.lock().unwrap().path().join("rust_out"));
+    let mut cmd = Command::new(binary);
+    cmd.args(args);
     let var = DynamicLibrary::envvar();
     let newpath = {
         let path = env::var_os(var).unwrap_or(OsString::new());
@@ -362,7 +403,134 @@ fn run_test(test: &str, cratename: &str, filename: &FileName, line: usize,
                        str::from_utf8(&out.stdout).unwrap_or(""),
                        str::from_utf8(&out.stderr).unwrap_or(""));
             }
+            if let Some(expected) = expected_output {
+                // Trailing whitespace is not shown to readers, so it doesn't have to match.
+                let output = String::from_utf8_lossy(&out.stdout);
+                if output.trim_right() != expected.trim_right() {
+                    panic!("test executable printed:\n{}\nbut the expected output is:\n{}\n",
+                           output, expected);
+                }
+            }
+        }
+    }
+}
+
+/// Doctests of one edition that are compiled together into a single test harness, with one test
+/// function each, which saves compiling and linking every one of them on its own.
+///
+/// The harness is compiled by the first of the tests to run. If it fails to compile, every test
+/// falls back to being compiled on its own, so that its errors are reported as usual.
+struct MergedDoctests {
+    edition: Edition,
+    cratename: String,
+    cfgs: Vec<String>,
+    libs: Vec<SearchPath>,
+    cg: CodegenOptions,
+    externs: Externs,
+    opts: TestOptions,
+    maybe_sysroot: Option<PathBuf>,
+    linker: Option<PathBuf>,
+    /// The bodies of the tests, and whether they should panic.
+    tests: Mutex<Vec<(String, bool)>>,
+    /// The target library directory and the path of the compiled harness, once compiled. The
+    /// temporary directory holding the harness lives as long as the tests do.
+    harness: Mutex<Option<Result<(PathBuf, TempDir), ()>>>,
+}
+
+impl MergedDoctests {
+    /// Adds a test to the harness, returning the name of its test function.
+    fn add_test(&self, body: String, should_panic: bool) -> String {
+        let mut tests = self.tests.lock().unwrap();
+        tests.push((body, should_panic));
+        format!("doctest_{}", tests.len() - 1)
+    }
+
+    /// Runs the test function `name` of the harness, or `run_alone` if the harness doesn't
+    /// compile.
+    fn run_test<F: FnOnce()>(&self, name: &str, run_alone: F) {
+        let harness = {
+            let mut harness = self.harness.lock().unwrap();
+            if harness.is_none() {
+                *harness = Some(self.compile());
+            }
+            match *harness {
+                Some(Ok((ref libdir, ref outdir))) => {
+                    Some((libdir.clone(), outdir.path().join("rust_out")))
+                }
+                _ => None,
+            }
+        };
+
+        match harness {
+            Some((libdir, binary)) => {
+                run_test_binary(&binary, libdir, &["--exact", name], false, None)
+            }
+            None => run_alone(),
+        }
+    }
+
+    fn compile(&self) -> Result<(PathBuf, TempDir), ()> {
+        let input = config::Input::Str {
+            name: FileName::Custom(format!("{} doctests", self.cratename)),
+            input: make_merged_test(&self.tests.lock().unwrap(), &self.cratename, &self.opts),
+        };
+        // The errors are reported by the tests compiled on their own.
+        let data = Arc::new(Mutex::new(Vec::new()));
+        let (libdir, outdir, compile_result) = compile_test(input,
+                                                            self.cfgs.clone(),
+                                                            self.libs.clone(),
+                                                            self.cg.clone(),
+                                                            self.externs.clone(),
+                                                            true,
+                                                            false,
+                                                            self.maybe_sysroot.clone(),
+                                                            self.linker.clone(),
+                                                            self.edition,
+                                                            &data);
+        compile_result.map(|()| (libdir, outdir))
+    }
+}
+
+/// Makes the source of the test harness holding the given merged doctests.
+fn make_merged_test(tests: &[(String, bool)], cratename: &str, opts: &TestOptions) -> String {
+    let mut prog = String::new();
+
+    if opts.attrs.is_empty() && !opts.display_warnings {
+        prog.push_str("#![allow(unused)]\n");
+    }
+    for attr in &opts.attrs {
+        prog.push_str(&format!("#![{}]\n", attr));
+    }
+    if !opts.no_crate_inject && cratename != "std" &&
+       tests.iter().any(|&(ref body, _)| body.contains(cratename)) {
+        prog.push_str(&format!("extern crate {};\n", cratename));
+    }
+
+    for (i, &(ref body, should_panic)) in tests.iter().enumerate() {
+        prog.push_str("\n#[test]\n");
+        if should_panic {
+            prog.push_str("#[should_panic]\n");
         }
+        prog.push_str(&format!("fn doctest_{}() {{\n{}\n}}\n", i, body));
+    }
+
+    prog
+}
+
+/// Returns the body of the test if it can be merged with others: it must not have crate
+/// attributes, `extern crate` items or a `main` function of its own.
+fn mergeable_test_body(s: &str, cratename: Option<&str>) -> Option<String> {
+    let (crate_attrs, everything_else, crates) = partition_source(s);
+    if !crate_attrs.trim().is_empty() {
+        return None;
+    }
+    let everything_else = everything_else.trim();
+    let (already_has_main, _) = check_for_main_and_extern_crate(s, &crates, everything_else,
+                                                                cratename);
+    if already_has_main {
+        None
+    } else {
+        Some(everything_else.to_string())
     }
 }
 
@@ -396,16 +564,47 @@ pub fn make_test(s: &str,
     // are intended to be crate attributes.
     prog.push_str(&crate_attrs);
 
-    // Uses libsyntax to parse the doctest and find if there's a main fn and the extern
-    // crate already is included.
-    let (already_has_main, already_has_extern_crate) = crate::syntax::with_globals(|| {
+    let (already_has_main, already_has_extern_crate) =
+        check_for_main_and_extern_crate(s, &crates, everything_else, cratename);
+
+    // Don't inject `extern crate std` because it's already injected by the
+    // compiler.
+    if !already_has_extern_crate && !opts.no_crate_inject && cratename != Some("std") {
+        if let Some(cratename) = cratename {
+            // Make sure its actually used if not included.
+            if s.contains(cratename) {
+                prog.push_str(&format!("extern crate {};\n", cratename));
+                line_offset += 1;
+            }
+        }
+    }
+
+    if dont_insert_main || already_has_main {
+        prog.push_str(everything_else);
+    } else {
+        prog.push_str("fn main() {\n");
+        line_offset += 1;
+        prog.push_str(everything_else);
+        prog.push_str("\n}");
+    }
+
+    (prog, line_offset)
+}
+
+/// Uses libsyntax to parse the doctest and find if there's a main fn and the extern
+/// crate already is included.
+fn check_for_main_and_extern_crate(s: &str,
+                                   crates: &str,
+                                   everything_else: &str,
+                                   cratename: Option<&str>) -> (bool, bool) {
+    crate::syntax::with_globals(|| {
         use crate::syntax::{ast, parse::{self, ParseSess}, source_map::FilePathMapping};
         use crate::syntax_pos::FileName;
         use errors::emitter::EmitterWriter;
         use errors::Handler;
 
         let filename = FileName::anon_source_code(s);
-        let source = crates + &everything_else;
+        let source = crates.to_string() + everything_else;
 
         // Any errors in parsing should also appear when the doctest is compiled for real, so just
         // send all the errors that libsyntax emits directly into a `Sink` instead of stderr.
@@ -465,30 +664,7 @@ pub fn make_test(s: &str,
         }
 
         (found_main, found_extern_crate)
-    });
-
-    // Don't inject `extern crate std` because it's already injected by the
-    // compiler.
-    if !already_has_extern_crate && !opts.no_crate_inject && cratename != Some("std") {
-        if let Some(cratename) = cratename {
-            // Make sure its actually used if not included.
-            if s.contains(cratename) {
-                prog.push_str(&format!("extern crate {};\n", cratename));
-                line_offset += 1;
-            }
-        }
-    }
-
-    if dont_insert_main || already_has_main {
-        prog.push_str(everything_else);
-    } else {
-        prog.push_str("fn main() {\n");
-        line_offset += 1;
-        prog.push_str(everything_else);
-        prog.push_str("\n}");
-    }
-
-    (prog, line_offset)
+    })
 }
 
 // FIXME(aburka): use a real parser to deal with multiline attributes
@@ -568,6 +744,8 @@ pub struct Collector {
     filename: Option<PathBuf>,
     linker: Option<PathBuf>,
     edition: Edition,
+    /// The harnesses the mergeable doctests are added to, one per edition.
+    merged: Vec<Arc<MergedDoctests>>,
 }
 
 impl Collector {
@@ -591,6 +769,7 @@ impl Collector {
             filename,
             linker,
             edition,
+            merged: Vec::new(),
         }
     }
 
@@ -602,6 +781,28 @@ impl Collector {
         self.position = position;
     }
 
+    /// Returns the harness merging the doctests of the given edition.
+    fn merged_doctests(&mut self, edition: Edition) -> Arc<MergedDoctests> {
+        if let Some(merged) = self.merged.iter().find(|m| m.edition == edition) {
+            return merged.clone();
+        }
+        let merged = Arc::new(MergedDoctests {
+            edition,
+            cratename: self.cratename.clone(),
+            cfgs: self.cfgs.clone(),
+            libs: self.libs.clone(),
+            cg: self.cg.clone(),
+            externs: self.externs.clone(),
+            opts: self.opts.clone(),
+            maybe_sysroot: self.maybe_sysroot.clone(),
+            linker: self.linker.clone(),
+            tests: Mutex::new(Vec::new()),
+            harness: Mutex::new(None),
+        });
+        self.merged.push(merged.clone());
+        merged
+    }
+
     fn get_filename(&self) -> FileName {
         if let Some(ref source_map) = self.source_map {
             let filename = source_map.span_to_filename(self.position);
@@ -634,14 +835,32 @@ impl Tester for Collector {
         let maybe_sysroot = self.maybe_sysroot.clone();
         let linker = self.linker.clone();
         let edition = config.edition.unwrap_or(self.edition);
+        // Tests that have to fail to compile, that are not run as plain functions, or whose output
+        // is checked, are always compiled on their own.
+        let mergeable = self.opts.merge_doctests && !config.ignore && !config.compile_fail &&
+                        !config.no_run && !config.test_harness && !config.check_output;
+        let body = if mergeable {
+            mergeable_test_body(&test, Some(&cratename[..]))
+        } else {
+            None
+        };
+        let merged = body.map(|body| {
+            let merged = self.merged_doctests(edition);
+            let test_fn = merged.add_test(body, config.should_panic);
+            (merged, test_fn)
+        });
         debug!("Creating test {}: {}", name, test);
         self.tests.push(testing::TestDescAndFn {
             desc: testing::TestDesc {
                 name: testing::DynTestName(name.clone()),
-                ignore: config.ignore,
+                // Ignored `check_output` tests are run to report that they can't be ignored.
+                ignore: config.ignore && !config.check_output,
                 // compiler failures are test failures
                 should_panic: testing::ShouldPanic::No,
                 allow_fail: config.allow_fail,
+                // The libtest built by stage0 has no time limits.
+                #[cfg(not(stage0))]
+                timeout: None,
             },
             testfn: testing::DynTestFn(box move || {
                 let panic = io::set_panic(None);
@@ -650,23 +869,32 @@ impl Tester for Collector {
                     rustc_driver::in_named_rustc_thread(name, move || with_globals(move || {
                         io::set_panic(panic);
                         io::set_print(print);
-                        run_test(&test,
-                                 &cratename,
-                                 &filename,
-                                 line,
-                                 cfgs,
-                                 libs,
-                                 cg,
-                                 externs,
-                                 config.should_panic,
-                                 config.no_run,
-                                 config.test_harness,
-                                 config.compile_fail,
-                                 config.error_codes,
-                                 &opts,
-                                 maybe_sysroot,
-                                 linker,
-                                 edition)
+                        let run_alone = move || {
+                            run_test(&test,
+                                     &cratename,
+                                     &filename,
+                                     line,
+                                     cfgs,
+                                     libs,
+                                     cg,
+                                     externs,
+                                     config.should_panic,
+                                     config.no_run,
+                                     config.test_harness,
+                                     config.compile_fail,
+                                     config.ignore,
+                                     config.error_codes,
+                                     config.check_output,
+                                     config.expected_output,
+                                     &opts,
+                                     maybe_sysroot,
+                                     linker,
+                                     edition)
+                        };
+                        match merged {
+                            Some((merged, test_fn)) => merged.run_test(&test_fn, run_alone),
+                            None => run_alone(),
+                        }
                     }))
                 } {
                     Ok(()) => (),
@@ -827,7 +1055,7 @@ impl<'a, 'hir> intravisit::Visitor<'hir> for HirCollector<'a, 'hir> {
 
 #[cfg(test)]
 mod tests {
-    use super::{TestOptions, make_test};
+    use super::{TestOptions, make_test, make_merged_test, mergeable_test_body};
 
     #[test]
     fn make_test_basic() {
@@ -887,6 +1115,7 @@ assert_eq!(2+2, 4);
             no_crate_inject: true,
             display_warnings: false,
             attrs: vec![],
+            merge_doctests: false,
         };
         let input =
 "use asdf::qwop;
@@ -1106,4 +1335,55 @@ assert_eq!(asdf::foo, 4);
         let output = make_test(input, Some("asdf"), false, &opts);
         assert_eq!(output, (expected, 3));
     }
+
+    #[test]
+    fn mergeable_tests() {
+        // Tests without crate attributes, `extern crate` or `main` of their own can be merged.
+        let input =
+"use asdf::qwop;
+assert_eq!(2+2, 4);";
+        assert_eq!(mergeable_test_body(input, Some("asdf")), Some(input.to_string()));
+
+        let input =
+"#![feature(sure)]
+assert_eq!(2+2, 4);";
+        assert_eq!(mergeable_test_body(input, Some("asdf")), None);
+
+        let input =
+"extern crate asdf;
+assert_eq!(2+2, 4);";
+        assert_eq!(mergeable_test_body(input, Some("asdf")), None);
+
+        let input =
+"fn main() {
+    assert_eq!(2+2, 4);
+}";
+        assert_eq!(mergeable_test_body(input, Some("asdf")), None);
+    }
+
+    #[test]
+    fn make_merged_test_basic() {
+        // Every test gets its own test function, and the crate is injected once if any uses it.
+        let opts = TestOptions::default();
+        let tests = vec![
+            ("use asdf::qwop;".to_string(), false),
+            ("panic!();".to_string(), true),
+        ];
+        let expected =
+"#![allow(unused)]
+extern crate asdf;
+
+#[test]
+fn doctest_0() {
+use asdf::qwop;
+}
+
+#[test]
+#[should_panic]
+fn doctest_1() {
+panic!();
+}
+".to_string();
+        assert_eq!(make_merged_test(&tests, "asdf", &opts), expected);
+    }
 }
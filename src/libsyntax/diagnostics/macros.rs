@@ -105,6 +105,19 @@ macro_rules! struct_span_err {
     })
 }
 
+/// Like `struct_span_err!`, but takes a `LocalizedMessage` that can be translated.
+#[macro_export]
+macro_rules! struct_span_err_localized {
+    ($session:expr, $span:expr, $code:ident, $message:expr) => ({
+        __diagnostic_used!($code);
+        $session.struct_span_err_localized(
+            $span,
+            $message,
+            $crate::errors::DiagnosticId::Error(stringify!($code).to_owned()),
+        )
+    })
+}
+
 #[macro_export]
 macro_rules! stringify_error_code {
     ($code:ident) => ({
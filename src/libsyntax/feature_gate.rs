@@ -493,6 +493,9 @@ declare_features! (
 
     // Allows calling `const unsafe fn` inside `unsafe` blocks in `const fn` functions.
     (active, min_const_unsafe_fn, "1.31.0", Some(55607), None),
+
+    // Allows a test to set its own time limit with `#[test_timeout = "SECS"]`.
+    (active, test_timeout, "1.33.0", None, None),
 );
 
 declare_features! (
@@ -1138,6 +1141,11 @@ pub const BUILTIN_ATTRIBUTES: &[(&str, AttributeType, AttributeGate)] = &[
                                  "allow_fail attribute is currently unstable",
                                  cfg_fn!(allow_fail))),
 
+    ("test_timeout", Normal, Gated(Stability::Unstable,
+                                   "test_timeout",
+                                   "test_timeout attribute is currently unstable",
+                                   cfg_fn!(test_timeout))),
+
     ("rustc_std_internal_symbol", Whitelisted, Gated(Stability::Unstable,
                                      "rustc_attrs",
                                      "this is an internal attribute that will \
@@ -0,0 +1,291 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A SARIF emitter for errors.
+//!
+//! [SARIF] is the format code-scanning services ingest the results of static analysis tools in.
+//! Unlike the JSON emitter, which writes each diagnostic as soon as it is emitted, this emitter
+//! collects the diagnostics of the whole compilation and writes a single SARIF log when it is
+//! dropped.
+//!
+//! Diagnostics are mapped to SARIF results as follows:
+//!
+//! * the error code or lint name is the `ruleId` of the result, and each of them is described
+//!   once in the `rules` of the tool, along with the explanation of the error code if there is
+//!   one;
+//! * primary spans are the `locations` of the result, while secondary spans and the spans of the
+//!   notes and helps are its `relatedLocations`. Span labels are the messages of the locations;
+//! * notes and helps without a span are appended to the message of the result;
+//! * suggestions are the `fixes` of the result, with their `Applicability` in the properties of
+//!   the fix;
+//! * diagnostics without a span, like "aborting due to previous error", are not about the code,
+//!   and are reported as notifications of the invocation of the compiler instead.
+//!
+//! [SARIF]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+
+use source_map::{SourceMap, FilePathMapping};
+use syntax_pos::{BytePos, Span, FileName};
+use errors::registry::Registry;
+use errors::{DiagnosticBuilder, SubDiagnostic, CodeSuggestion, Substitution, SourceMapper};
+use errors::{DiagnosticId, Level};
+use errors::emitter::Emitter;
+
+use rustc_data_structures::sync::{self, Lrc};
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use rustc_serialize::json::{as_pretty_json, Json, ToJson};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+pub struct SarifEmitter {
+    dst: Box<dyn Write + Send>,
+    registry: Option<Registry>,
+    sm: Lrc<dyn SourceMapper + sync::Send + sync::Sync>,
+    /// The rules of the diagnostics emitted so far, by id.
+    rules: BTreeMap<String, Json>,
+    results: Vec<Json>,
+    notifications: Vec<Json>,
+    /// Whether an error has been emitted.
+    failed: bool,
+}
+
+impl SarifEmitter {
+    pub fn stderr(registry: Option<Registry>, source_map: Lrc<SourceMap>) -> SarifEmitter {
+        SarifEmitter::new(Box::new(io::stderr()), registry, source_map)
+    }
+
+    pub fn basic() -> SarifEmitter {
+        let file_path_mapping = FilePathMapping::empty();
+        SarifEmitter::stderr(None, Lrc::new(SourceMap::new(file_path_mapping)))
+    }
+
+    pub fn new(dst: Box<dyn Write + Send>,
+               registry: Option<Registry>,
+               source_map: Lrc<SourceMap>) -> SarifEmitter {
+        SarifEmitter {
+            dst,
+            registry,
+            sm: source_map,
+            rules: BTreeMap::new(),
+            results: vec![],
+            notifications: vec![],
+            failed: false,
+        }
+    }
+
+    fn add_rule(&mut self, code: &DiagnosticId) -> String {
+        let (id, is_error_code) = match *code {
+            DiagnosticId::Error(ref id) => (id.clone(), true),
+            DiagnosticId::Lint(ref id) => (id.clone(), false),
+        };
+        if !self.rules.contains_key(&id) {
+            let mut rule = vec![("id", id.to_json())];
+            if is_error_code {
+                let uri = format!("https://doc.rust-lang.org/error-index.html#{}", id);
+                rule.push(("helpUri", uri.to_json()));
+            }
+            let explanation = self.registry.as_ref()
+                                           .and_then(|registry| registry.find_description(&id));
+            if let Some(explanation) = explanation {
+                rule.push(("fullDescription", object(vec![
+                    ("text", explanation.to_json()),
+                    ("markdown", explanation.to_json()),
+                ])));
+            }
+            self.rules.insert(id.clone(), object(rule));
+        }
+        id
+    }
+
+    fn region(&self, lo: BytePos, hi: BytePos) -> (FileName, Json) {
+        let start = self.sm.lookup_char_pos(lo);
+        let end = self.sm.lookup_char_pos(hi);
+        let region = object(vec![
+            ("startLine", start.line.to_json()),
+            // SARIF columns are 1-based, and counted in characters as set by the `columnKind` of
+            // the run.
+            ("startColumn", (start.col.0 + 1).to_json()),
+            ("endLine", end.line.to_json()),
+            ("endColumn", (end.col.0 + 1).to_json()),
+        ]);
+        (start.file.name.clone(), region)
+    }
+
+    fn location(&self, span: Span, label: Option<String>) -> Json {
+        let (file, region) = self.region(span.lo(), span.hi());
+        let mut location = vec![("physicalLocation", object(vec![
+            ("artifactLocation", artifact_location(&file)),
+            ("region", region),
+        ]))];
+        if let Some(label) = label {
+            location.push(("message", message(&label)));
+        }
+        object(location)
+    }
+
+    fn fix(&self, suggestion: &CodeSuggestion, substitution: &Substitution) -> Json {
+        // The replacements of a fix are grouped by the file they change.
+        let mut changes = BTreeMap::<String, (FileName, Vec<Json>)>::new();
+        for part in &substitution.parts {
+            let (file, region) = self.region(part.span.lo(), part.span.hi());
+            changes.entry(file.to_string())
+                   .or_insert_with(|| (file, vec![]))
+                   .1
+                   .push(object(vec![
+                       ("deletedRegion", region),
+                       ("insertedContent", object(vec![("text", part.snippet.to_json())])),
+                   ]));
+        }
+        let changes = changes.into_iter().map(|(_, (file, replacements))| {
+            object(vec![
+                ("artifactLocation", artifact_location(&file)),
+                ("replacements", Json::Array(replacements)),
+            ])
+        }).collect();
+
+        object(vec![
+            ("description", message(&suggestion.msg)),
+            ("artifactChanges", Json::Array(changes)),
+            ("properties", object(vec![
+                ("applicability", format!("{:?}", suggestion.applicability).to_json()),
+            ])),
+        ])
+    }
+
+    /// Adds the spans of `child` to `related_locations`, or its message to `text` if it has none.
+    fn add_child(&self,
+                 child: &SubDiagnostic,
+                 text: &mut String,
+                 related_locations: &mut Vec<Json>) {
+        let child_message = format!("{}: {}", child.level.to_str(), child.message());
+        let span = child.render_span.as_ref().unwrap_or(&child.span);
+        let spans = span.primary_spans()
+                        .iter()
+                        .filter(|span| !span.is_dummy())
+                        .collect::<Vec<_>>();
+        if spans.is_empty() {
+            text.push('\n');
+            text.push_str(&child_message);
+        }
+        for &&span in &spans {
+            related_locations.push(self.location(span, Some(child_message.clone())));
+        }
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit(&mut self, db: &DiagnosticBuilder) {
+        let level = match db.level {
+            Level::Bug | Level::Fatal | Level::PhaseFatal | Level::Error => {
+                self.failed = true;
+                "error"
+            }
+            Level::Warning => "warning",
+            Level::Note | Level::Help => "note",
+            Level::Cancelled | Level::FailureNote => return,
+        };
+
+        let mut text = db.message();
+        let mut locations = vec![];
+        let mut related_locations = vec![];
+        for span_label in db.span.span_labels() {
+            if span_label.span.is_dummy() {
+                continue;
+            }
+            let location = self.location(span_label.span, span_label.label);
+            if span_label.is_primary {
+                locations.push(location);
+            } else {
+                related_locations.push(location);
+            }
+        }
+        for child in &db.children {
+            self.add_child(child, &mut text, &mut related_locations);
+        }
+
+        if locations.is_empty() {
+            self.notifications.push(object(vec![
+                ("level", level.to_json()),
+                ("message", message(&text)),
+            ]));
+            return;
+        }
+
+        let mut result = vec![
+            ("level", level.to_json()),
+            ("message", message(&text)),
+            ("locations", Json::Array(locations)),
+        ];
+        if let Some(ref code) = db.code {
+            let id = self.add_rule(code);
+            result.push(("ruleId", id.to_json()));
+        }
+        if !related_locations.is_empty() {
+            result.push(("relatedLocations", Json::Array(related_locations)));
+        }
+        let fixes = db.suggestions.iter().flat_map(|suggestion| {
+            suggestion.substitutions.iter().map(move |substitution| (suggestion, substitution))
+        }).map(|(suggestion, substitution)| self.fix(suggestion, substitution)).collect::<Vec<_>>();
+        if !fixes.is_empty() {
+            result.push(("fixes", Json::Array(fixes)));
+        }
+        self.results.push(object(result));
+    }
+
+    fn should_show_explain(&self) -> bool {
+        // The explanations of the error codes are in the rules of the log.
+        false
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        let rules = self.rules.values().cloned().collect();
+        let run = object(vec![
+            ("tool", object(vec![
+                ("driver", object(vec![
+                    ("name", "rustc".to_json()),
+                    ("informationUri", "https://www.rust-lang.org/".to_json()),
+                    ("rules", Json::Array(rules)),
+                ])),
+            ])),
+            ("invocations", Json::Array(vec![object(vec![
+                ("executionSuccessful", (!self.failed).to_json()),
+                ("toolExecutionNotifications", Json::Array(self.notifications.clone())),
+            ])])),
+            ("columnKind", "unicodeCodePoints".to_json()),
+            ("results", Json::Array(self.results.clone())),
+        ]);
+        let log = object(vec![
+            ("$schema", SARIF_SCHEMA.to_json()),
+            ("version", SARIF_VERSION.to_json()),
+            ("runs", Json::Array(vec![run])),
+        ]);
+        // Panicking here could abort the compiler while it is unwinding, and there is nowhere
+        // left to report the error to anyway.
+        let _ = writeln!(&mut self.dst, "{}", as_pretty_json(&log));
+    }
+}
+
+fn object(fields: Vec<(&str, Json)>) -> Json {
+    Json::Object(fields.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+}
+
+fn message(text: &str) -> Json {
+    object(vec![("text", text.to_json())])
+}
+
+fn artifact_location(file: &FileName) -> Json {
+    // URIs only use forward slashes.
+    let uri = file.to_string().replace('\\', "/");
+    object(vec![("uri", uri.to_json())])
+}
@@ -658,6 +658,19 @@ impl MultiSpan {
         self.span_labels.push((span, label));
     }
 
+    /// Replaces the first label `before` of `span` with `after`. Returns true if the label was
+    /// found.
+    pub fn replace_span_label(&mut self, span: Span, before: &str, after: String) -> bool {
+        let found = self.span_labels.iter_mut().find(|label| label.0 == span && label.1 == before);
+        match found {
+            Some(span_label) => {
+                span_label.1 = after;
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Selects the first primary span (if any).
     pub fn primary_span(&self) -> Option<Span> {
         self.primary_spans.first().cloned()
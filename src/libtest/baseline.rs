@@ -0,0 +1,200 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Benchmark baselines: the samples of a benchmark run saved under a name
+//! with `--save-baseline`, which a later run compares itself against with
+//! `--baseline`.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+use stats::{self, Stats};
+
+/// The p-value below which a difference between two runs is considered real
+/// rather than noise.
+const SIGNIFICANCE_LEVEL: f64 = 0.05;
+
+/// How a benchmark changed compared to the baseline.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BenchChange {
+    Improved,
+    Regressed,
+    NoChange,
+}
+
+impl fmt::Display for BenchChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            BenchChange::Improved => "improved",
+            BenchChange::Regressed => "regressed",
+            BenchChange::NoChange => "no change",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BenchComparison {
+    /// Median ns/iter of the baseline
+    pub baseline_median: f64,
+    /// Change of the median compared to the baseline, in percent
+    pub change_pct: f64,
+    /// p-value of the Mann-Whitney U test between both sets of samples
+    pub p_value: f64,
+    pub change: BenchChange,
+}
+
+/// The ns/iter samples of every benchmark of a run, by name.
+pub struct Baseline(BTreeMap<String, Vec<f64>>);
+
+impl Baseline {
+    pub fn new() -> Baseline {
+        Baseline(BTreeMap::new())
+    }
+
+    pub fn insert(&mut self, name: &str, samples: &[f64]) {
+        self.0.insert(name.to_owned(), samples.to_vec());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Compares `samples` against the baseline samples of the benchmark
+    /// `name`. Changes of the median smaller than `noise_threshold` percent,
+    /// or that are not statistically significant, count as no change.
+    pub fn compare(
+        &self,
+        name: &str,
+        samples: &[f64],
+        noise_threshold: f64,
+    ) -> Option<BenchComparison> {
+        let baseline = match self.0.get(name) {
+            Some(baseline) if !baseline.is_empty() && !samples.is_empty() => baseline,
+            _ => return None,
+        };
+
+        let baseline_median = baseline.median();
+        let change_pct = if baseline_median == 0.0 {
+            0.0
+        } else {
+            (samples.median() - baseline_median) / baseline_median * 100.0
+        };
+        let p_value = stats::mann_whitney_u(baseline, samples);
+        let change = if p_value >= SIGNIFICANCE_LEVEL || change_pct.abs() <= noise_threshold {
+            BenchChange::NoChange
+        } else if change_pct > 0.0 {
+            BenchChange::Regressed
+        } else {
+            BenchChange::Improved
+        };
+
+        Some(BenchComparison {
+            baseline_median,
+            change_pct,
+            p_value,
+            change,
+        })
+    }
+
+    /// Loads the baseline saved as `name` by an earlier run of this test
+    /// binary.
+    pub fn load(name: &str) -> io::Result<Baseline> {
+        let path = baseline_path(name)?;
+        let file = File::open(&path).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("could not read baseline `{}` from {}: {}", name, path.display(), e),
+            )
+        })?;
+
+        let mut baseline = Baseline::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            let mut parts = line.splitn(2, '\t');
+            let bench = parts.next().unwrap_or("");
+            let samples = parts
+                .next()
+                .unwrap_or("")
+                .split_whitespace()
+                .map(|s| s.parse::<f64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("malformed baseline file {}", path.display()),
+                    )
+                })?;
+            baseline.0.insert(bench.to_owned(), samples);
+        }
+        Ok(baseline)
+    }
+
+    /// Saves the baseline as `name`, replacing any earlier baseline with the
+    /// same name.
+    pub fn save(&self, name: &str) -> io::Result<()> {
+        let path = baseline_path(name)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut file = File::create(&path)?;
+        for (bench, samples) in &self.0 {
+            let samples = samples.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+            writeln!(file, "{}\t{}", bench, samples.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Baselines are stored as `<dir>/<name>/<test binary>`, where `<dir>` is
+/// `RUST_TEST_BASELINE_DIR` or else `bench-baselines` next to the test binary.
+fn baseline_path(name: &str) -> io::Result<PathBuf> {
+    let exe = env::current_exe()?;
+    let dir = match env::var_os("RUST_TEST_BASELINE_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => exe
+            .parent()
+            .map(|p| p.join("bench-baselines"))
+            .unwrap_or_else(|| PathBuf::from("bench-baselines")),
+    };
+    let file_name = exe.file_stem().map(|s| s.to_owned()).unwrap_or_default();
+    Ok(dir.join(name).join(file_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BenchChange, Baseline};
+
+    #[test]
+    fn compare_against_baseline() {
+        let old = (0..50).map(|i| 100.0 + (i % 5) as f64).collect::<Vec<_>>();
+        let slower = old.iter().map(|s| s * 1.5).collect::<Vec<_>>();
+        let mut baseline = Baseline::new();
+        baseline.insert("bench", &old);
+
+        let same = baseline.compare("bench", &old, 2.0).unwrap();
+        assert_eq!(same.change, BenchChange::NoChange);
+        assert_eq!(same.change_pct, 0.0);
+
+        let regressed = baseline.compare("bench", &slower, 2.0).unwrap();
+        assert_eq!(regressed.change, BenchChange::Regressed);
+        assert!(regressed.p_value < 0.05);
+
+        // Within the noise threshold, so not reported even though significant.
+        let noise = baseline.compare("bench", &slower, 60.0).unwrap();
+        assert_eq!(noise.change, BenchChange::NoChange);
+
+        assert!(baseline.compare("other", &old, 2.0).is_none());
+    }
+}
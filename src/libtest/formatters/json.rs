@@ -48,10 +48,14 @@ impl<T: Write> JsonFormatter<T> {
 }
 
 impl<T: Write> OutputFormatter for JsonFormatter<T> {
-    fn write_run_start(&mut self, test_count: usize) -> io::Result<()> {
+    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> io::Result<()> {
+        let shuffle_seed_json = match shuffle_seed {
+            Some(seed) => format!(r#", "shuffle_seed": {}"#, seed),
+            None => String::new(),
+        };
         self.write_message(&*format!(
-            r#"{{ "type": "suite", "event": "started", "test_count": {} }}"#,
-            test_count
+            r#"{{ "type": "suite", "event": "started", "test_count": {}{} }}"#,
+            test_count, shuffle_seed_json
         ))
     }
 
@@ -91,6 +95,27 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
                 Some(format!(r#""message": "{}""#, EscapedString(m))),
             ),
 
+            TrTimedFail => self.write_event(
+                "test",
+                desc.name.as_slice(),
+                "failed",
+                Some(r#""reason": "time limit exceeded""#.to_owned()),
+            ),
+
+            TrFlaky => {
+                // `stdout` is the output of the first failed attempt.
+                let extra_data = if stdout.len() > 0 {
+                    Some(format!(
+                        r#""stdout": "{}""#,
+                        EscapedString(String::from_utf8_lossy(stdout))
+                    ))
+                } else {
+                    None
+                };
+
+                self.write_event("test", desc.name.as_slice(), "flaky", extra_data)
+            }
+
             TrIgnored => self.write_event("test", desc.name.as_slice(), "ignored", None),
 
             TrAllowedFail => {
@@ -107,12 +132,23 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
                     format!(r#", "mib_per_second": {}"#, bs.mb_s)
                 };
 
+                let comparison = match bs.comparison {
+                    Some(ref cmp) => format!(
+                        ", \"baseline_median\": {}, \
+                         \"change_pct\": {:.2}, \
+                         \"p_value\": {:.4}, \
+                         \"change\": \"{}\"",
+                        cmp.baseline_median as usize, cmp.change_pct, cmp.p_value, cmp.change
+                    ),
+                    None => String::new(),
+                };
+
                 let line = format!(
                     "{{ \"type\": \"bench\", \
                      \"name\": \"{}\", \
                      \"median\": {}, \
-                     \"deviation\": {}{} }}",
-                    desc.name, median, deviation, mbps
+                     \"deviation\": {}{}{} }}",
+                    desc.name, median, deviation, mbps, comparison
                 );
 
                 self.write_message(&*line)
@@ -134,6 +170,7 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
              \"passed\": {}, \
              \"failed\": {}, \
              \"allowed_fail\": {}, \
+             \"flaky\": {}, \
              \"ignored\": {}, \
              \"measured\": {}, \
              \"filtered_out\": {} }}",
@@ -141,6 +178,7 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
             state.passed,
             state.failed + state.allowed_fail,
             state.allowed_fail,
+            state.flaky,
             state.ignored,
             state.measured,
             state.filtered_out
@@ -0,0 +1,249 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::*;
+use std::collections::HashMap;
+
+/// Collects the results of a run and writes them out as a single JUnit XML
+/// document once the run is finished.
+pub(crate) struct JunitFormatter<T> {
+    out: OutputLocation<T>,
+    run_start: Option<Instant>,
+    shuffle_seed: Option<u64>,
+    /// When each currently running test was started
+    test_starts: HashMap<String, Instant>,
+    results: Vec<(TestDesc, TestResult, Vec<u8>, Duration)>,
+}
+
+impl<T: Write> JunitFormatter<T> {
+    pub fn new(out: OutputLocation<T>) -> Self {
+        Self {
+            out,
+            run_start: None,
+            shuffle_seed: None,
+            test_starts: HashMap::new(),
+            results: Vec::new(),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn output_location(&self) -> &OutputLocation<T> {
+        &self.out
+    }
+
+    fn write_message(&mut self, s: &str) -> io::Result<()> {
+        self.out.write_all(s.as_ref())?;
+        self.out.write_all(b"\n")
+    }
+
+    fn write_properties(&mut self) -> io::Result<()> {
+        let mut properties = Vec::new();
+        if let Some(seed) = self.shuffle_seed {
+            properties.push(("shuffle_seed".to_owned(), seed.to_string()));
+        }
+        for &(ref desc, ref result, _, _) in &self.results {
+            if let TrBench(ref bs) = *result {
+                let name = desc.name.as_slice();
+                let median = bs.ns_iter_summ.median as usize;
+                let deviation = (bs.ns_iter_summ.max - bs.ns_iter_summ.min) as usize;
+                properties.push((format!("{}.median_ns", name), median.to_string()));
+                properties.push((format!("{}.deviation_ns", name), deviation.to_string()));
+                if bs.mb_s != 0 {
+                    properties.push((format!("{}.mib_per_second", name), bs.mb_s.to_string()));
+                }
+                if let Some(ref cmp) = bs.comparison {
+                    let change_pct = format!("{:.2}", cmp.change_pct);
+                    properties.push((format!("{}.change_pct", name), change_pct));
+                    properties.push((format!("{}.change", name), cmp.change.to_string()));
+                }
+            }
+        }
+
+        if properties.is_empty() {
+            return Ok(());
+        }
+        self.write_message("<properties>")?;
+        for (name, value) in properties {
+            self.write_message(&*format!(
+                r#"<property name="{}" value="{}"/>"#,
+                EscapedXml(&name),
+                EscapedXml(&value)
+            ))?;
+        }
+        self.write_message("</properties>")
+    }
+
+    fn write_testcase(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        stdout: &[u8],
+        duration: Duration,
+    ) -> io::Result<()> {
+        // Split `path::to::test` into the "class" `path::to` and the name `test`.
+        let full_name = desc.name.as_slice();
+        let (classname, name) = match full_name.rfind("::") {
+            Some(pos) => (&full_name[..pos], &full_name[pos + 2..]),
+            None => ("", full_name),
+        };
+        let opening = format!(
+            r#"<testcase classname="{}" name="{}" time="{}""#,
+            EscapedXml(classname),
+            EscapedXml(name),
+            fmt_secs(duration)
+        );
+
+        let failure_message = match *result {
+            TrOk | TrBench(_) => return self.write_message(&*format!("{}/>", opening)),
+            TrIgnored => {
+                return self.write_message(&*format!("{}><skipped/></testcase>", opening))
+            }
+            TrAllowedFail => {
+                return self.write_message(&*format!(
+                    r#"{}><skipped message="failed (allowed)"/></testcase>"#,
+                    opening
+                ))
+            }
+            TrFailed => "failed".to_owned(),
+            TrFailedMsg(ref m) => m.clone(),
+            TrTimedFail => "time limit exceeded".to_owned(),
+            TrFlaky => {
+                // Reported as passed, along with the failure of the first attempt.
+                self.write_message(&*format!("{}>", opening))?;
+                self.write_message(r#"<flakyFailure type="assert" message="failed"/>"#)?;
+                if !stdout.is_empty() {
+                    self.write_message(&*format!(
+                        "<system-out>{}</system-out>",
+                        EscapedXml(&String::from_utf8_lossy(stdout))
+                    ))?;
+                }
+                return self.write_message("</testcase>");
+            }
+        };
+
+        self.write_message(&*format!("{}>", opening))?;
+        self.write_message(&*format!(
+            r#"<failure type="assert" message="{}"/>"#,
+            EscapedXml(&failure_message)
+        ))?;
+        if !stdout.is_empty() {
+            self.write_message(&*format!(
+                "<system-out>{}</system-out>",
+                EscapedXml(&String::from_utf8_lossy(stdout))
+            ))?;
+        }
+        self.write_message("</testcase>")
+    }
+}
+
+impl<T: Write> OutputFormatter for JunitFormatter<T> {
+    fn write_run_start(&mut self, _test_count: usize, shuffle_seed: Option<u64>) -> io::Result<()> {
+        // Nothing is written until the run is finished, since the enclosing
+        // `testsuite` element needs the totals.
+        self.run_start = Some(Instant::now());
+        self.shuffle_seed = shuffle_seed;
+        Ok(())
+    }
+
+    fn write_test_start(&mut self, desc: &TestDesc) -> io::Result<()> {
+        self.test_starts.insert(desc.name.as_slice().to_owned(), Instant::now());
+        Ok(())
+    }
+
+    fn write_timeout(&mut self, _desc: &TestDesc) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn write_result(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        stdout: &[u8],
+    ) -> io::Result<()> {
+        let duration = match self.test_starts.remove(desc.name.as_slice()) {
+            Some(start) => start.elapsed(),
+            None => Duration::new(0, 0),
+        };
+        self.results.push((desc.clone(), result.clone(), stdout.to_vec(), duration));
+        Ok(())
+    }
+
+    fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
+        let duration = match self.run_start {
+            Some(start) => start.elapsed(),
+            None => Duration::new(0, 0),
+        };
+
+        self.write_message(r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        self.write_message("<testsuites>")?;
+        self.write_message(&*format!(
+            "<testsuite name=\"test\" package=\"test\" id=\"0\" tests=\"{}\" errors=\"0\" \
+             failures=\"{}\" skipped=\"{}\" time=\"{}\">",
+            self.results.len(),
+            state.failed,
+            state.ignored + state.allowed_fail,
+            fmt_secs(duration)
+        ))?;
+        self.write_properties()?;
+
+        let results = ::std::mem::replace(&mut self.results, Vec::new());
+        for (desc, result, stdout, duration) in results {
+            self.write_testcase(&desc, &result, &stdout, duration)?;
+        }
+
+        self.write_message("</testsuite>")?;
+        self.write_message("</testsuites>")?;
+
+        Ok(state.failed == 0)
+    }
+}
+
+fn fmt_secs(duration: Duration) -> String {
+    format!("{}.{:03}", duration.as_secs(), duration.subsec_millis())
+}
+
+/// A formatting utility used to print strings as XML attribute values or text.
+///
+/// Characters that cannot appear in an XML 1.0 document at all are dropped.
+struct EscapedXml<S: AsRef<str>>(S);
+
+impl<S: AsRef<str>> ::std::fmt::Display for EscapedXml<S> {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        let s = self.0.as_ref();
+        let mut start = 0;
+
+        for (i, c) in s.char_indices() {
+            let escaped = match c {
+                '<' => "&lt;",
+                '>' => "&gt;",
+                '&' => "&amp;",
+                '"' => "&quot;",
+                '\'' => "&apos;",
+                '\t' | '\n' | '\r' => continue,
+                '\x00'...'\x1f' => "",
+                _ => continue,
+            };
+
+            if start < i {
+                f.write_str(&s[start..i])?;
+            }
+
+            f.write_str(escaped)?;
+
+            start = i + c.len_utf8();
+        }
+
+        if start != s.len() {
+            f.write_str(&s[start..])?;
+        }
+
+        Ok(())
+    }
+}
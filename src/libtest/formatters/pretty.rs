@@ -56,6 +56,14 @@ impl<T: Write> PrettyFormatter<T> {
         self.write_short_result("FAILED (allowed)", term::color::YELLOW)
     }
 
+    pub fn write_time_failed(&mut self) -> io::Result<()> {
+        self.write_short_result("FAILED (time limit exceeded)", term::color::RED)
+    }
+
+    pub fn write_flaky(&mut self) -> io::Result<()> {
+        self.write_short_result("FLAKY", term::color::YELLOW)
+    }
+
     pub fn write_bench(&mut self) -> io::Result<()> {
         self.write_pretty("bench", term::color::CYAN)
     }
@@ -120,6 +128,32 @@ impl<T: Write> PrettyFormatter<T> {
         Ok(())
     }
 
+    pub fn write_flaky_tests(&mut self, state: &ConsoleTestState) -> io::Result<()> {
+        self.write_plain("\nflaky tests:\n")?;
+        let mut flaky = Vec::new();
+        let mut first_fail_out = String::new();
+        for &(ref f, ref stdout) in &state.flaky_tests {
+            flaky.push(f.name.to_string());
+            if !stdout.is_empty() {
+                first_fail_out.push_str(&format!("---- {} first failure stdout ----\n", f.name));
+                let output = String::from_utf8_lossy(stdout);
+                first_fail_out.push_str(&output);
+                first_fail_out.push_str("\n");
+            }
+        }
+        if !first_fail_out.is_empty() {
+            self.write_plain("\n")?;
+            self.write_plain(&first_fail_out)?;
+        }
+
+        self.write_plain("\nflaky tests:\n")?;
+        flaky.sort();
+        for name in &flaky {
+            self.write_plain(&format!("    {}\n", name))?;
+        }
+        Ok(())
+    }
+
     pub fn write_failures(&mut self, state: &ConsoleTestState) -> io::Result<()> {
         self.write_plain("\nfailures:\n")?;
         let mut failures = Vec::new();
@@ -155,9 +189,13 @@ impl<T: Write> PrettyFormatter<T> {
 }
 
 impl<T: Write> OutputFormatter for PrettyFormatter<T> {
-    fn write_run_start(&mut self, test_count: usize) -> io::Result<()> {
+    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> io::Result<()> {
         let noun = if test_count != 1 { "tests" } else { "test" };
-        self.write_plain(&format!("\nrunning {} {}\n", test_count, noun))
+        let shuffle_seed_msg = match shuffle_seed {
+            Some(seed) => format!(" (shuffle seed: {})", seed),
+            None => String::new(),
+        };
+        self.write_plain(&format!("\nrunning {} {}{}\n", test_count, noun, shuffle_seed_msg))
     }
 
     fn write_test_start(&mut self, desc: &TestDesc) -> io::Result<()> {
@@ -186,6 +224,8 @@ impl<T: Write> OutputFormatter for PrettyFormatter<T> {
                 self.write_bench()?;
                 self.write_plain(&format!(": {}\n", fmt_bench_samples(bs)))
             }
+            TrTimedFail => self.write_time_failed(),
+            TrFlaky => self.write_flaky(),
         }
     }
 
@@ -204,6 +244,9 @@ impl<T: Write> OutputFormatter for PrettyFormatter<T> {
         if state.options.display_output {
             self.write_successes(state)?;
         }
+        if state.flaky > 0 {
+            self.write_flaky_tests(state)?;
+        }
         let success = state.failed == 0;
         if !success {
             self.write_failures(state)?;
@@ -218,10 +261,17 @@ impl<T: Write> OutputFormatter for PrettyFormatter<T> {
             self.write_pretty("FAILED", term::color::RED)?;
         }
 
+        let flaky = if state.flaky > 0 {
+            format!(" {} flaky;", state.flaky)
+        } else {
+            String::new()
+        };
         let s = if state.allowed_fail > 0 {
             format!(
-                ". {} passed; {} failed ({} allowed); {} ignored; {} measured; {} filtered out\n\n",
+                ". {} passed;{} {} failed ({} allowed); {} ignored; {} measured; \
+                 {} filtered out\n\n",
                 state.passed,
+                flaky,
                 state.failed + state.allowed_fail,
                 state.allowed_fail,
                 state.ignored,
@@ -230,8 +280,13 @@ impl<T: Write> OutputFormatter for PrettyFormatter<T> {
             )
         } else {
             format!(
-                ". {} passed; {} failed; {} ignored; {} measured; {} filtered out\n\n",
-                state.passed, state.failed, state.ignored, state.measured, state.filtered_out
+                ". {} passed;{} {} failed; {} ignored; {} measured; {} filtered out\n\n",
+                state.passed,
+                flaky,
+                state.failed,
+                state.ignored,
+                state.measured,
+                state.filtered_out
             )
         };
 
@@ -0,0 +1,430 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Support for `--isolate`, which runs every test in a fresh copy of the
+//! test binary so that a test that exits, aborts or crashes only takes
+//! itself down.
+//!
+//! The parent process re-executes itself with the same arguments and with
+//! `ISOLATED_TEST_VAR` set to the name of the test to run. `test_main` notices
+//! the variable, runs just that test and writes the outcome to the file named
+//! in `ISOLATED_RESULT_VAR`, tagged with a nonce chosen by the parent. The variables are cleared before
+//! the test runs, so the test body cannot forge a result: a child that exits
+//! without reporting one, whatever its exit code, is a failed test.
+
+use std::collections::hash_map::RandomState;
+use std::env;
+use std::fs::{self, File};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::mem;
+use std::process::{self, Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::{__rust_begin_short_backtrace, calc_result, convert_benchmarks_to_tests, Concurrent,
+            MonitorMsg, TestDesc, TestDescAndFn, TestFn, TestResult};
+
+/// Environment variable holding the name of the test a child process runs.
+pub const ISOLATED_TEST_VAR: &str = "__RUST_TEST_ISOLATED";
+
+/// Environment variable holding `<nonce>:<path>`, where a child process
+/// writes the outcome of its test.
+pub const ISOLATED_RESULT_VAR: &str = "__RUST_TEST_ISOLATED_RESULT";
+
+/// Outcome of a test, as reported by the child process that ran it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ChildOutcome {
+    Passed,
+    Failed,
+}
+
+impl ChildOutcome {
+    fn as_str(self) -> &'static str {
+        match self {
+            ChildOutcome::Passed => "passed",
+            ChildOutcome::Failed => "failed",
+        }
+    }
+}
+
+/// Returns the name of the test to run if this process is an isolated child.
+pub fn isolated_test_name() -> Option<String> {
+    env::var(ISOLATED_TEST_VAR).ok()
+}
+
+/// Runs the test called `name` out of `tests` in this process, reports the
+/// outcome to the parent and exits. Never returns.
+pub fn run_isolated_test(name: &str, tests: Vec<TestDescAndFn>) -> ! {
+    // Processes spawned by the test, which may well be this binary again,
+    // must not take themselves for isolated children, and the test itself
+    // must not learn where the result goes.
+    let report = env::var(ISOLATED_RESULT_VAR).ok();
+    env::remove_var(ISOLATED_TEST_VAR);
+    env::remove_var(ISOLATED_RESULT_VAR);
+
+    // The parent only isolates benchmarks when running them as tests.
+    let tests = convert_benchmarks_to_tests(tests);
+    let test = match tests.into_iter().find(|t| t.desc.name.as_slice() == name) {
+        Some(test) => test,
+        None => {
+            eprintln!("error: no test named `{}` in this binary", name);
+            process::exit(101);
+        }
+    };
+
+    let TestDescAndFn { mut desc, testfn } = test;
+    let result = catch_unwind(AssertUnwindSafe(|| match testfn {
+        TestFn::StaticTestFn(f) => __rust_begin_short_backtrace(f),
+        TestFn::DynTestFn(f) => __rust_begin_short_backtrace(f),
+        _ => unreachable!(),
+    }));
+
+    // `allow_fail` is applied by the parent, since it also covers crashes.
+    desc.allow_fail = false;
+    let outcome = match calc_result(&desc, result) {
+        TestResult::TrOk => ChildOutcome::Passed,
+        TestResult::TrFailedMsg(msg) => {
+            eprintln!("note: {}", msg);
+            ChildOutcome::Failed
+        }
+        _ => ChildOutcome::Failed,
+    };
+
+    if let Some(report) = report {
+        if let Err(e) = write_report(&report, outcome) {
+            eprintln!("error: failed to report the test result: {}", e);
+            process::exit(101);
+        }
+    }
+    process::exit(0)
+}
+
+fn write_report(report: &str, outcome: ChildOutcome) -> io::Result<()> {
+    let mut parts = report.splitn(2, ':');
+    let nonce = parts.next().unwrap_or("");
+    let path = match parts.next() {
+        Some(path) => path,
+        None => return Err(io::Error::new(io::ErrorKind::InvalidInput, "malformed report path")),
+    };
+    let mut file = File::create(path)?;
+    writeln!(file, "{}", nonce)?;
+    writeln!(file, "{}", outcome.as_str())
+}
+
+/// The file a child process reports its outcome to.
+///
+/// The file is named after a fresh nonce, which the child also writes into it
+/// so that a stale or foreign file is never taken for a result.
+struct ResultFile {
+    nonce: String,
+    path: PathBuf,
+}
+
+impl ResultFile {
+    fn new() -> ResultFile {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let mut hasher = RandomState::new().build_hasher();
+        COUNTER.fetch_add(1, Ordering::SeqCst).hash(&mut hasher);
+        let nonce = format!("{:016x}", hasher.finish());
+        let path = env::temp_dir().join(format!("rust-test-{}-{}", process::id(), nonce));
+        ResultFile { nonce, path }
+    }
+
+    /// Value of `ISOLATED_RESULT_VAR` for the child.
+    fn env_value(&self) -> String {
+        format!("{}:{}", self.nonce, self.path.display())
+    }
+
+    /// Returns the outcome the child reported, if it reported a valid one.
+    fn read(&self) -> Option<ChildOutcome> {
+        let contents = fs::read_to_string(&self.path).ok()?;
+        let mut lines = contents.lines();
+        if lines.next() != Some(&*self.nonce) {
+            return None;
+        }
+        let outcome = match lines.next() {
+            Some("passed") => ChildOutcome::Passed,
+            Some("failed") => ChildOutcome::Failed,
+            _ => return None,
+        };
+        if lines.next().is_some() {
+            return None;
+        }
+        Some(outcome)
+    }
+}
+
+impl Drop for ResultFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Runs `desc` in a child process and sends the result to `monitor_ch`.
+///
+/// If `time_limit` is given the child is killed once it has run for that
+/// long, and the test is reported as timed out.
+pub fn run_test_in_child_process(
+    desc: TestDesc,
+    monitor_ch: Sender<MonitorMsg>,
+    nocapture: bool,
+    time_limit: Option<Duration>,
+    concurrency: Concurrent,
+) {
+    let name = desc.name.clone();
+    let runtest = move || {
+        let (result, output) = match spawn_and_wait(&desc, nocapture, time_limit) {
+            Ok((status, outcome, output)) => (calc_child_result(&desc, status, outcome), output),
+            Err(e) => {
+                let msg = format!("failed to run test in a child process: {}", e);
+                (TestResult::TrFailedMsg(msg), Vec::new())
+            }
+        };
+        monitor_ch.send((desc.clone(), result, output)).unwrap();
+    };
+
+    if concurrency == Concurrent::Yes {
+        let cfg = thread::Builder::new().name(name.as_slice().to_owned());
+        cfg.spawn(runtest).unwrap();
+    } else {
+        runtest();
+    }
+}
+
+/// Runs the child and collects its output and the outcome it reported.
+/// Returns `None` as the status if the child had to be killed because it
+/// exceeded `time_limit`.
+fn spawn_and_wait(
+    desc: &TestDesc,
+    nocapture: bool,
+    time_limit: Option<Duration>,
+) -> io::Result<(Option<ExitStatus>, Option<ChildOutcome>, Vec<u8>)> {
+    let result_file = ResultFile::new();
+    let mut command = Command::new(env::current_exe()?);
+    // Harnesses such as `rustdoc --test` build their list of tests from the
+    // command line, so the child gets the same one.
+    command.args(env::args_os().skip(1));
+    command.env(ISOLATED_TEST_VAR, desc.name.as_slice());
+    command.env(ISOLATED_RESULT_VAR, result_file.env_value());
+    if !nocapture {
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    }
+    let mut child = command.spawn()?;
+
+    // Drain both pipes while the child runs, so that it cannot block on a
+    // full pipe.
+    let stdout = child.stdout.take().map(read_in_background);
+    let stderr = child.stderr.take().map(read_in_background);
+
+    let status = match time_limit {
+        Some(limit) => wait_with_time_limit(&mut child, limit)?,
+        None => Some(child.wait()?),
+    };
+
+    let mut output = Vec::new();
+    for reader in stdout.into_iter().chain(stderr) {
+        // A process started by a killed child may still hold the pipe open,
+        // so only wait for the end of the output if the child exited.
+        output.extend(if status.is_some() { reader.finish() } else { reader.abandon() });
+    }
+    Ok((status, result_file.read(), output))
+}
+
+/// Reads a pipe of the child on a separate thread.
+struct BackgroundReader {
+    buf: Arc<Mutex<Vec<u8>>>,
+    done: Receiver<()>,
+}
+
+impl BackgroundReader {
+    /// Waits for the end of the output and returns all of it.
+    fn finish(self) -> Vec<u8> {
+        let _ = self.done.recv();
+        self.take()
+    }
+
+    /// Returns the output read so far, giving the reader a moment to catch
+    /// up but without waiting for the end of the output.
+    fn abandon(self) -> Vec<u8> {
+        let _ = self.done.recv_timeout(Duration::from_millis(100));
+        self.take()
+    }
+
+    fn take(&self) -> Vec<u8> {
+        let mut buf = self.buf.lock().unwrap_or_else(|e| e.into_inner());
+        mem::replace(&mut *buf, Vec::new())
+    }
+}
+
+fn read_in_background<R: Read + Send + 'static>(mut r: R) -> BackgroundReader {
+    let buf = Arc::new(Mutex::new(Vec::new()));
+    let (tx, done) = channel();
+    let thread_buf = buf.clone();
+    thread::spawn(move || {
+        let mut chunk = [0; 4096];
+        loop {
+            match r.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(n) => thread_buf.lock().unwrap().extend_from_slice(&chunk[..n]),
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(_) => break,
+            }
+        }
+        let _ = tx.send(());
+    });
+    BackgroundReader { buf, done }
+}
+
+fn wait_with_time_limit(child: &mut Child, limit: Duration) -> io::Result<Option<ExitStatus>> {
+    let deadline = Instant::now() + limit;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if Instant::now() >= deadline {
+            child.kill()?;
+            child.wait()?;
+            return Ok(None);
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+fn calc_child_result(
+    desc: &TestDesc,
+    status: Option<ExitStatus>,
+    outcome: Option<ChildOutcome>,
+) -> TestResult {
+    let result = match status {
+        None => return TestResult::TrTimedFail,
+        Some(status) => match (status.success(), outcome) {
+            (true, Some(ChildOutcome::Passed)) => return TestResult::TrOk,
+            (true, Some(ChildOutcome::Failed)) => TestResult::TrFailed,
+            _ => TestResult::TrFailedMsg(format!(
+                "test process terminated unexpectedly ({})",
+                fmt_exit_status(status)
+            )),
+        },
+    };
+
+    if desc.allow_fail {
+        TestResult::TrAllowedFail
+    } else {
+        result
+    }
+}
+
+#[cfg(unix)]
+fn fmt_exit_status(status: ExitStatus) -> String {
+    use std::os::unix::process::ExitStatusExt;
+
+    match status.signal() {
+        Some(signal) => match signal_name(signal) {
+            Some(name) => format!("signal: {}, {}", signal, name),
+            None => format!("signal: {}", signal),
+        },
+        None => status.to_string(),
+    }
+}
+
+#[cfg(not(unix))]
+fn fmt_exit_status(status: ExitStatus) -> String {
+    status.to_string()
+}
+
+#[cfg(unix)]
+fn signal_name(signal: i32) -> Option<&'static str> {
+    use libc;
+
+    Some(match signal {
+        libc::SIGABRT => "SIGABRT",
+        libc::SIGBUS => "SIGBUS",
+        libc::SIGFPE => "SIGFPE",
+        libc::SIGILL => "SIGILL",
+        libc::SIGINT => "SIGINT",
+        libc::SIGKILL => "SIGKILL",
+        libc::SIGPIPE => "SIGPIPE",
+        libc::SIGQUIT => "SIGQUIT",
+        libc::SIGSEGV => "SIGSEGV",
+        libc::SIGTERM => "SIGTERM",
+        libc::SIGTRAP => "SIGTRAP",
+        _ => return None,
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::ExitStatus;
+    use super::{calc_child_result, ChildOutcome, ResultFile};
+    use {ShouldPanic, StaticTestName, TestDesc, TestResult};
+
+    fn desc(allow_fail: bool) -> TestDesc {
+        TestDesc {
+            name: StaticTestName("whatever"),
+            ignore: false,
+            should_panic: ShouldPanic::No,
+            allow_fail,
+            #[cfg(not(stage0))]
+            timeout: None,
+        }
+    }
+
+    #[test]
+    fn child_outcomes() {
+        let exited = ExitStatus::from_raw(0);
+        let passed = Some(ChildOutcome::Passed);
+        let failed = Some(ChildOutcome::Failed);
+        assert!(calc_child_result(&desc(false), Some(exited), passed) == TestResult::TrOk);
+        assert!(calc_child_result(&desc(false), Some(exited), failed) == TestResult::TrFailed);
+        assert!(calc_child_result(&desc(true), Some(exited), failed) == TestResult::TrAllowedFail);
+        assert!(calc_child_result(&desc(false), None, None) == TestResult::TrTimedFail);
+    }
+
+    #[test]
+    fn child_exit_without_report_fails() {
+        // Whatever a test exits with, only the reported outcome counts.
+        for &code in &[0, 50, 51] {
+            let status = ExitStatus::from_raw(code << 8);
+            match calc_child_result(&desc(false), Some(status), None) {
+                TestResult::TrFailedMsg(_) => {}
+                _ => panic!("expected a failure for exit code {}", code),
+            }
+        }
+    }
+
+    #[test]
+    fn child_crash_reports_signal() {
+        let segv = ExitStatus::from_raw(::libc::SIGSEGV);
+        match calc_child_result(&desc(false), Some(segv), Some(ChildOutcome::Passed)) {
+            TestResult::TrFailedMsg(msg) => assert!(msg.contains("SIGSEGV")),
+            _ => panic!("expected a failure"),
+        }
+    }
+
+    #[test]
+    fn result_file_checks_nonce() {
+        let file = ResultFile::new();
+        super::write_report(&file.env_value(), ChildOutcome::Passed).unwrap();
+        assert_eq!(file.read(), Some(ChildOutcome::Passed));
+
+        let other = ResultFile::new();
+        let forged = format!("{}:{}", other.nonce, file.path.display());
+        super::write_report(&forged, ChildOutcome::Passed).unwrap();
+        assert_eq!(file.read(), None);
+    }
+}
@@ -68,7 +68,7 @@ use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::any::Any;
 use std::boxed::FnBox;
 use std::cmp;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::fmt;
 use std::fs::File;
@@ -85,19 +85,26 @@ use std::process;
 
 const TEST_WARN_TIMEOUT_S: u64 = 60;
 const QUIET_MODE_MAX_COLUMN: usize = 100; // insert a '\n' after 100 tests in quiet mode
+/// Changes of a benchmark smaller than this many percent of the baseline are
+/// reported as noise, unless `--noise-threshold` says otherwise.
+pub const DEFAULT_NOISE_THRESHOLD: f64 = 2.0;
 
 // to be used by rustc to compile tests in libtest
 pub mod test {
     pub use {assert_test_result, filter_tests, parse_opts, run_test, test_main, test_main_static,
              Bencher, DynTestFn, DynTestName, Metric, MetricMap, Options, RunIgnored, ShouldPanic,
              StaticBenchFn, StaticTestFn, StaticTestName, TestDesc, TestDescAndFn, TestName,
-             TestOpts, TestResult, TrFailed, TrFailedMsg, TrIgnored, TrOk};
+             TestOpts, TestResult, TrFailed, TrFailedMsg, TrFlaky, TrIgnored, TrOk, TrTimedFail};
 }
 
 pub mod stats;
+mod baseline;
 mod formatters;
+mod isolation;
 
-use formatters::{JsonFormatter, OutputFormatter, PrettyFormatter, TerseFormatter};
+use baseline::{Baseline, BenchComparison};
+
+use formatters::{JsonFormatter, JunitFormatter, OutputFormatter, PrettyFormatter, TerseFormatter};
 
 /// Whether to execute tests concurrently or not
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -214,6 +221,7 @@ impl fmt::Debug for TestFn {
 pub struct Bencher {
     mode: BenchMode,
     summary: Option<stats::Summary>,
+    samples: Vec<f64>,
     pub bytes: u64,
 }
 
@@ -238,6 +246,11 @@ pub struct TestDesc {
     pub ignore: bool,
     pub should_panic: ShouldPanic,
     pub allow_fail: bool,
+    /// Number of seconds after which the test is considered hung and
+    /// failed. Overrides `TestOpts::test_timeout`.
+    // The `#[test]` harness of the stage0 compiler doesn't set this field.
+    #[cfg(not(stage0))]
+    pub timeout: Option<u64>,
 }
 
 #[derive(Debug)]
@@ -280,6 +293,13 @@ impl Options {
 // The default console test runner. It accepts the command line
 // arguments and a vector of test_descs.
 pub fn test_main(args: &[String], tests: Vec<TestDescAndFn>, options: Options) {
+    // When running in isolation, the parent process tells us which test to
+    // run, and the usual command line is only there for the harness to build
+    // the same list of tests.
+    if let Some(name) = isolation::isolated_test_name() {
+        isolation::run_isolated_test(&name, tests);
+    }
+
     let mut opts = match parse_opts(args) {
         Some(Ok(o)) => o,
         Some(Err(msg)) => {
@@ -359,6 +379,7 @@ pub enum OutputFormat {
     Pretty,
     Terse,
     Json,
+    Junit,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -368,6 +389,14 @@ pub enum RunIgnored {
     Only,
 }
 
+/// Selects the part of the tests a single test runner out of `count` runs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Shard {
+    /// The shard to run, `0 <= index < count`
+    pub index: usize,
+    pub count: usize,
+}
+
 #[derive(Debug)]
 pub struct TestOpts {
     pub list: bool,
@@ -382,6 +411,15 @@ pub struct TestOpts {
     pub format: OutputFormat,
     pub test_threads: Option<usize>,
     pub skip: Vec<String>,
+    pub shuffle: bool,
+    pub shuffle_seed: Option<u64>,
+    pub test_timeout: Option<u64>,
+    pub isolate: bool,
+    pub save_baseline: Option<String>,
+    pub baseline: Option<String>,
+    pub noise_threshold: f64,
+    pub shard: Option<Shard>,
+    pub retries: usize,
     pub options: Options,
 }
 
@@ -401,6 +439,15 @@ impl TestOpts {
             format: OutputFormat::Pretty,
             test_threads: None,
             skip: vec![],
+            shuffle: false,
+            shuffle_seed: None,
+            test_timeout: None,
+            isolate: false,
+            save_baseline: None,
+            baseline: None,
+            noise_threshold: DEFAULT_NOISE_THRESHOLD,
+            shard: None,
+            retries: 0,
             options: Options::new(),
         }
     }
@@ -455,6 +502,67 @@ fn optgroups() -> getopts::Options {
             "exact",
             "Exactly match filters rather than by substring",
         )
+        .optflag(
+            "",
+            "shuffle",
+            "Run tests in random order",
+        )
+        .optopt(
+            "",
+            "shuffle-seed",
+            "Run tests in random order; seed the random number generator with SEED",
+            "SEED",
+        )
+        .optopt(
+            "",
+            "test-timeout",
+            "Fail tests that run for longer than SECS seconds",
+            "SECS",
+        )
+        .optflag(
+            "",
+            "isolate",
+            "Run each test in its own process",
+        )
+        .optopt(
+            "",
+            "save-baseline",
+            "Save the benchmark results under NAME for later comparison",
+            "NAME",
+        )
+        .optopt(
+            "",
+            "baseline",
+            "Compare the benchmark results against those saved under NAME",
+            "NAME",
+        )
+        .optopt(
+            "",
+            "noise-threshold",
+            "Changes compared to the baseline smaller than PCT percent are \
+             reported as no change (default 2)",
+            "PCT",
+        )
+        .optopt(
+            "",
+            "shard-index",
+            "Only run the tests of shard INDEX (counting from 0), \
+             requires --shard-count",
+            "INDEX",
+        )
+        .optopt(
+            "",
+            "shard-count",
+            "Split the tests into N shards, requires --shard-index",
+            "N",
+        )
+        .optopt(
+            "",
+            "retries",
+            "Run failing tests up to N more times, and report tests \
+             that pass on a later attempt as flaky",
+            "N",
+        )
         .optopt(
             "",
             "color",
@@ -470,8 +578,9 @@ fn optgroups() -> getopts::Options {
             "Configure formatting of output:
             pretty = Print verbose output;
             terse  = Display one character per test;
-            json   = Output a json document",
-            "pretty|terse|json",
+            json   = Output a json document;
+            junit  = Output a JUnit XML document",
+            "pretty|terse|json|junit",
         )
         .optopt(
             "Z",
@@ -499,6 +608,38 @@ All tests have their standard output and standard error captured by default.
 This can be overridden with the --nocapture flag or setting RUST_TEST_NOCAPTURE
 environment variable to a value other than "0". Logging is not captured by default.
 
+The order in which tests are run can be randomized with the --shuffle flag or by
+setting the RUST_TEST_SHUFFLE environment variable to a value other than "0".
+The seed used for the shuffle is printed at the start of the run; pass it back
+with --shuffle-seed (or RUST_TEST_SHUFFLE_SEED) to replay the same order.
+
+Tests that run for longer than the --test-timeout limit are reported as failed
+and the remaining tests keep running. Since a test thread cannot be killed, a
+timed out test keeps running in the background until the test binary exits,
+unless tests are run with --isolate.
+
+With --isolate every test is run in a new process started from the test binary,
+so a test that exits, aborts or crashes is reported as failed instead of ending
+the whole run. Benchmarks are still run in the main process.
+
+The samples of a benchmark run can be saved with --save-baseline and compared
+against by a later run with --baseline. A benchmark is reported as improved or
+regressed if its median changed by more than the --noise-threshold and the
+change is statistically significant. Baselines are saved next to the test
+binary, or in the RUST_TEST_BASELINE_DIR directory if that is set.
+
+With --retries a failed test is run again, up to the given number of times. A
+test that passes on a later attempt is reported as flaky instead of as passed or
+failed, together with the output of its first failure; with --format terse a
+flaky test is shown as `R`, for retried. Only tests defined with
+#[test] can be retried, and a test that hangs past its --test-timeout limit is
+not retried either; the output of such a test notes that it was not retried.
+
+The tests can be split between several machines with --shard-count and
+--shard-index. Which shard a test belongs to only depends on its name, so the
+split is the same everywhere and adding a test does not move any other test to
+a different shard. --list shows the tests of the selected shard.
+
 Test Attributes:
 
     #[test]        - Indicates a function is a test to be run. This function
@@ -512,7 +653,9 @@ Test Attributes:
     #[ignore]      - When applied to a function which is already attributed as a
                      test, then the test runner will ignore these tests during
                      normal test runs. Running with --ignored or --include-ignored will run
-                     these tests."#,
+                     these tests.
+    #[test_timeout = "SECS"] - Fail the test if it runs for longer than SECS
+                     seconds, regardless of the --test-timeout flag."#,
         usage = options.usage(&message)
     );
 }
@@ -598,6 +741,119 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
         };
     }
 
+    let mut shuffle = matches.opt_present("shuffle");
+    if !shuffle {
+        shuffle = match env::var("RUST_TEST_SHUFFLE") {
+            Ok(val) => &val != "0",
+            Err(_) => false,
+        };
+    }
+
+    let shuffle_seed = match matches.opt_str("shuffle-seed") {
+        Some(n_str) => Some(n_str),
+        None => env::var("RUST_TEST_SHUFFLE_SEED").ok(),
+    };
+    let shuffle_seed = match shuffle_seed {
+        Some(n_str) => match n_str.parse::<u64>() {
+            Ok(n) => Some(n),
+            Err(e) => {
+                return Some(Err(format!(
+                    "argument for --shuffle-seed must be a number \
+                     (error: {})",
+                    e
+                )))
+            }
+        },
+        None => None,
+    };
+
+    if !allow_unstable && (shuffle || shuffle_seed.is_some()) {
+        return Some(Err(
+            "The \"shuffle\" and \"shuffle-seed\" flags are only accepted on the \
+             nightly compiler".into(),
+        ));
+    }
+
+    let test_timeout = match matches.opt_str("test-timeout") {
+        Some(n_str) => match n_str.parse::<u64>() {
+            Ok(0) => return Some(Err("argument for --test-timeout must not be 0".to_string())),
+            Ok(n) => Some(n),
+            Err(e) => {
+                return Some(Err(format!(
+                    "argument for --test-timeout must be a number > 0 \
+                     (error: {})",
+                    e
+                )))
+            }
+        },
+        None => None,
+    };
+
+    if !allow_unstable && test_timeout.is_some() {
+        return Some(Err(
+            "The \"test-timeout\" flag is only accepted on the nightly compiler".into(),
+        ));
+    }
+
+    let isolate = matches.opt_present("isolate");
+    if !allow_unstable && isolate {
+        return Some(Err(
+            "The \"isolate\" flag is only accepted on the nightly compiler".into(),
+        ));
+    }
+
+    let save_baseline = matches.opt_str("save-baseline");
+    let baseline = matches.opt_str("baseline");
+    let noise_threshold = match matches.opt_str("noise-threshold") {
+        Some(pct_str) => match pct_str.parse::<f64>() {
+            Ok(pct) if pct >= 0.0 => Some(pct),
+            _ => {
+                return Some(Err(format!(
+                    "argument for --noise-threshold must be a non-negative number (was {})",
+                    pct_str
+                )))
+            }
+        },
+        None => None,
+    };
+    if !allow_unstable
+        && (save_baseline.is_some() || baseline.is_some() || noise_threshold.is_some())
+    {
+        return Some(Err(
+            "The \"save-baseline\", \"baseline\" and \"noise-threshold\" flags are only \
+             accepted on the nightly compiler".into(),
+        ));
+    }
+
+    let retries = match matches.opt_str("retries") {
+        Some(n_str) => match n_str.parse::<usize>() {
+            Ok(n) => n,
+            Err(e) => {
+                return Some(Err(format!(
+                    "argument for --retries must be a number (error: {})",
+                    e
+                )))
+            }
+        },
+        None => 0,
+    };
+    if !allow_unstable && retries > 0 {
+        return Some(Err(
+            "The \"retries\" flag is only accepted on the nightly compiler".into(),
+        ));
+    }
+
+    let shard = match parse_shard(&matches) {
+        Ok(shard) => shard,
+        Err(e) => return Some(Err(e)),
+    };
+    if !allow_unstable && shard.is_some() {
+        return Some(Err(
+            "The \"shard-index\" and \"shard-count\" flags are only accepted on the \
+             nightly compiler".into(),
+        ));
+    }
+
     let test_threads = match matches.opt_str("test-threads") {
         Some(n_str) => match n_str.parse::<usize>() {
             Ok(0) => return Some(Err("argument for --test-threads must not be 0".to_string())),
@@ -639,10 +895,18 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
             }
             OutputFormat::Json
         }
+        Some("junit") => {
+            if !allow_unstable {
+                return Some(Err(
+                    "The \"junit\" format is only accepted on the nightly compiler".into(),
+                ));
+            }
+            OutputFormat::Junit
+        }
 
         Some(v) => {
             return Some(Err(format!(
-                "argument for --format must be pretty, terse, or json (was \
+                "argument for --format must be pretty, terse, json, or junit (was \
                  {})",
                 v
             )))
@@ -662,16 +926,51 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
         format,
         test_threads,
         skip: matches.opt_strs("skip"),
+        shuffle,
+        shuffle_seed,
+        test_timeout,
+        isolate,
+        save_baseline,
+        baseline,
+        noise_threshold: noise_threshold.unwrap_or(DEFAULT_NOISE_THRESHOLD),
+        shard,
+        retries,
         options: Options::new(),
     };
 
     Some(Ok(test_opts))
 }
 
+fn parse_shard(matches: &getopts::Matches) -> Result<Option<Shard>, String> {
+    fn parse_number(matches: &getopts::Matches, name: &str) -> Result<Option<usize>, String> {
+        match matches.opt_str(name) {
+            Some(n_str) => n_str.parse::<usize>().map(Some).map_err(|e| {
+                format!("argument for --{} must be a number (error: {})", name, e)
+            }),
+            None => Ok(None),
+        }
+    }
+
+    match (parse_number(matches, "shard-index")?, parse_number(matches, "shard-count")?) {
+        (None, None) => Ok(None),
+        (Some(_), None) | (None, Some(_)) => Err(
+            "the options --shard-index and --shard-count must be used together".to_string()
+        ),
+        (Some(_), Some(0)) => Err("argument for --shard-count must not be 0".to_string()),
+        (Some(index), Some(count)) if index >= count => Err(format!(
+            "argument for --shard-index must be less than --shard-count ({})",
+            count
+        )),
+        (Some(index), Some(count)) => Ok(Some(Shard { index, count })),
+    }
+}
+
 #[derive(Clone, PartialEq)]
 pub struct BenchSamples {
     ns_iter_summ: stats::Summary,
+    ns_iter_samples: Vec<f64>,
     mb_s: usize,
+    comparison: Option<BenchComparison>,
 }
 
 #[derive(Clone, PartialEq)]
@@ -682,6 +981,9 @@ pub enum TestResult {
     TrIgnored,
     TrAllowedFail,
     TrBench(BenchSamples),
+    TrTimedFail,
+    /// Failed at first, but passed when run again
+    TrFlaky,
 }
 
 unsafe impl Send for TestResult {}
@@ -714,12 +1016,17 @@ struct ConsoleTestState {
     failed: usize,
     ignored: usize,
     allowed_fail: usize,
+    flaky: usize,
     filtered_out: usize,
     measured: usize,
     metrics: MetricMap,
     failures: Vec<(TestDesc, Vec<u8>)>,
     not_failures: Vec<(TestDesc, Vec<u8>)>,
+    flaky_tests: Vec<(TestDesc, Vec<u8>)>,
     options: Options,
+    baseline: Option<Baseline>,
+    bench_samples: Baseline,
+    noise_threshold: f64,
 }
 
 impl ConsoleTestState {
@@ -736,12 +1043,20 @@ impl ConsoleTestState {
             failed: 0,
             ignored: 0,
             allowed_fail: 0,
+            flaky: 0,
             filtered_out: 0,
             measured: 0,
             metrics: MetricMap::new(),
             failures: Vec::new(),
             not_failures: Vec::new(),
+            flaky_tests: Vec::new(),
             options: opts.options,
+            baseline: match opts.baseline {
+                Some(ref name) => Some(Baseline::load(name)?),
+                None => None,
+            },
+            bench_samples: Baseline::new(),
+            noise_threshold: opts.noise_threshold,
         })
     }
 
@@ -763,13 +1078,26 @@ impl ConsoleTestState {
                 TrIgnored => "ignored".to_owned(),
                 TrAllowedFail => "failed (allowed)".to_owned(),
                 TrBench(ref bs) => fmt_bench_samples(bs),
+                TrTimedFail => "failed (time limit exceeded)".to_owned(),
+                TrFlaky => "flaky".to_owned(),
             },
             test.name
         ))
     }
 
+    /// Records the samples of a benchmark, and compares them against the
+    /// baseline if there is one.
+    fn record_bench_samples(&mut self, test: &TestDesc, mut bs: BenchSamples) -> BenchSamples {
+        let name = test.name.as_slice();
+        if let Some(ref baseline) = self.baseline {
+            bs.comparison = baseline.compare(name, &bs.ns_iter_samples, self.noise_threshold);
+        }
+        self.bench_samples.insert(name, &bs.ns_iter_samples);
+        bs
+    }
+
     fn current_test_count(&self) -> usize {
-        self.passed + self.failed + self.ignored + self.measured + self.allowed_fail
+        self.passed + self.failed + self.ignored + self.measured + self.allowed_fail + self.flaky
     }
 }
 
@@ -816,6 +1144,14 @@ pub fn fmt_bench_samples(bs: &BenchSamples) -> String {
             .write_fmt(format_args!(" = {} MB/s", bs.mb_s))
             .unwrap();
     }
+    if let Some(ref cmp) = bs.comparison {
+        output
+            .write_fmt(format_args!(
+                " ({:+.2}% vs. baseline, p = {:.2}: {})",
+                cmp.change_pct, cmp.p_value, cmp.change
+            ))
+            .unwrap();
+    }
     output
 }
 
@@ -867,11 +1203,16 @@ pub fn list_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Res
             writeln!(output, "")?;
         }
 
+        let shard = match opts.shard {
+            Some(shard) => format!(" in shard {} of {}", shard.index, shard.count),
+            None => String::new(),
+        };
         writeln!(
             output,
-            "{}, {}",
+            "{}, {}{}",
             plural(ntest, "test"),
-            plural(nbench, "benchmark")
+            plural(nbench, "benchmark"),
+            shard
         )?;
     }
 
@@ -886,14 +1227,18 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
         out: &mut dyn OutputFormatter,
     ) -> io::Result<()> {
         match (*event).clone() {
-            TeFiltered(ref filtered_tests) => {
+            TeFiltered(ref filtered_tests, shuffle_seed) => {
                 st.total = filtered_tests.len();
-                out.write_run_start(filtered_tests.len())
+                out.write_run_start(filtered_tests.len(), shuffle_seed)
             }
             TeFilteredOut(filtered_out) => Ok(st.filtered_out = filtered_out),
             TeWait(ref test) => out.write_test_start(test),
             TeTimeout(ref test) => out.write_timeout(test),
             TeResult(test, result, stdout) => {
+                let result = match result {
+                    TrBench(bs) => TrBench(st.record_bench_samples(&test, bs)),
+                    result => result,
+                };
                 st.write_log_result(&test, &result)?;
                 out.write_result(&test, &result, &*stdout)?;
                 match result {
@@ -921,6 +1266,18 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
                         stdout.extend_from_slice(format!("note: {}", msg).as_bytes());
                         st.failures.push((test, stdout));
                     }
+                    TrFlaky => {
+                        st.flaky += 1;
+                        st.flaky_tests.push((test, stdout));
+                    }
+                    TrTimedFail => {
+                        st.failed += 1;
+                        let mut stdout = stdout;
+                        stdout.extend_from_slice(
+                            b"note: test did not finish within its time limit",
+                        );
+                        st.failures.push((test, stdout));
+                    }
                 }
                 Ok(())
             }
@@ -954,6 +1311,7 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
             is_multithreaded,
         )),
         OutputFormat::Json => Box::new(JsonFormatter::new(output)),
+        OutputFormat::Junit => Box::new(JunitFormatter::new(output)),
     };
     let mut st = ConsoleTestState::new(opts)?;
     fn len_if_padded(t: &TestDescAndFn) -> usize {
@@ -967,6 +1325,16 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
 
     assert!(st.current_test_count() == st.total);
 
+    if let Some(ref name) = opts.save_baseline {
+        // Without `--bench`, or if no benchmark matched the filters, there is
+        // nothing to save, and saving would erase the previous baseline.
+        if st.bench_samples.is_empty() {
+            eprintln!("warning: no benchmark ran, so the baseline `{}` was not saved", name);
+        } else {
+            st.bench_samples.save(name)?;
+        }
+    }
+
     return out.write_run_finish(&st);
 }
 
@@ -977,6 +1345,8 @@ fn should_sort_failures_before_printing_them() {
         ignore: false,
         should_panic: ShouldPanic::No,
         allow_fail: false,
+        #[cfg(not(stage0))]
+        timeout: None,
     };
 
     let test_b = TestDesc {
@@ -984,6 +1354,8 @@ fn should_sort_failures_before_printing_them() {
         ignore: false,
         should_panic: ShouldPanic::No,
         allow_fail: false,
+        #[cfg(not(stage0))]
+        timeout: None,
     };
 
     let mut out = PrettyFormatter::new(Raw(Vec::new()), false, 10, false);
@@ -995,12 +1367,17 @@ fn should_sort_failures_before_printing_them() {
         failed: 0,
         ignored: 0,
         allowed_fail: 0,
+        flaky: 0,
         filtered_out: 0,
         measured: 0,
         metrics: MetricMap::new(),
         failures: vec![(test_b, Vec::new()), (test_a, Vec::new())],
         options: Options::new(),
         not_failures: Vec::new(),
+        flaky_tests: Vec::new(),
+        baseline: None,
+        bench_samples: Baseline::new(),
+        noise_threshold: DEFAULT_NOISE_THRESHOLD,
     };
 
     out.write_failures(&st).unwrap();
@@ -1014,6 +1391,66 @@ fn should_sort_failures_before_printing_them() {
     assert!(apos < bpos);
 }
 
+#[test]
+fn junit_formatter_reports_failures_and_escapes_output() {
+    let test_a = TestDesc {
+        name: StaticTestName("module::a"),
+        ignore: false,
+        should_panic: ShouldPanic::No,
+        allow_fail: false,
+        #[cfg(not(stage0))]
+        timeout: None,
+    };
+
+    let test_b = TestDesc {
+        name: StaticTestName("b"),
+        ignore: true,
+        should_panic: ShouldPanic::No,
+        allow_fail: false,
+        #[cfg(not(stage0))]
+        timeout: None,
+    };
+
+    let mut out = JunitFormatter::new(Raw(Vec::new()));
+    out.write_run_start(2, None).unwrap();
+    out.write_test_start(&test_a).unwrap();
+    out.write_result(&test_a, &TrFailed, b"<oops> & \x07").unwrap();
+    out.write_test_start(&test_b).unwrap();
+    out.write_result(&test_b, &TrIgnored, b"").unwrap();
+
+    let st = ConsoleTestState {
+        log_out: None,
+        total: 2,
+        passed: 0,
+        failed: 1,
+        ignored: 1,
+        allowed_fail: 0,
+        flaky: 0,
+        filtered_out: 0,
+        measured: 0,
+        metrics: MetricMap::new(),
+        failures: Vec::new(),
+        options: Options::new(),
+        not_failures: Vec::new(),
+        flaky_tests: Vec::new(),
+        baseline: None,
+        bench_samples: Baseline::new(),
+        noise_threshold: DEFAULT_NOISE_THRESHOLD,
+    };
+
+    assert!(!out.write_run_finish(&st).unwrap());
+    let s = match out.output_location() {
+        &Raw(ref m) => String::from_utf8_lossy(&m[..]).into_owned(),
+        &Pretty(_) => unreachable!(),
+    };
+
+    assert!(s.contains(r#"tests="2" errors="0" failures="1" skipped="1""#));
+    assert!(s.contains(r#"<testcase classname="module" name="a""#));
+    assert!(s.contains("<system-out>&lt;oops&gt; &amp; </system-out>"));
+    assert!(s.contains(r#"<testcase classname="" name="b""#));
+    assert!(s.contains("<skipped/>"));
+}
+
 fn use_color(opts: &TestOpts) -> bool {
     match opts.color {
         AutoColor => !opts.nocapture && stdout_isatty(),
@@ -1054,7 +1491,7 @@ fn stdout_isatty() -> bool {
 
 #[derive(Clone)]
 pub enum TestEvent {
-    TeFiltered(Vec<TestDesc>),
+    TeFiltered(Vec<TestDesc>, Option<u64>),
     TeWait(TestDesc),
     TeResult(TestDesc, TestResult, Vec<u8>),
     TeTimeout(TestDesc),
@@ -1077,7 +1514,7 @@ pub fn run_tests<F>(opts: &TestOpts, tests: Vec<TestDescAndFn>, mut callback: F)
 where
     F: FnMut(TestEvent) -> io::Result<()>,
 {
-    use std::collections::{self, HashMap};
+    use std::collections::{self, HashMap, HashSet};
     use std::hash::BuildHasherDefault;
     use std::sync::mpsc::RecvTimeoutError;
     // Use a deterministic hasher
@@ -1103,9 +1540,21 @@ where
     let filtered_out = tests_len - filtered_tests.len();
     callback(TeFilteredOut(filtered_out))?;
 
+    // Shuffle before handing out the tests, so the dispatch order only
+    // depends on the seed and not on the number of threads.
+    let shuffle_seed = get_shuffle_seed(opts);
+    let filtered_tests = match shuffle_seed {
+        Some(seed) => {
+            let mut filtered_tests = filtered_tests;
+            shuffle_tests(seed, &mut filtered_tests);
+            filtered_tests
+        }
+        None => filtered_tests,
+    };
+
     let filtered_descs = filtered_tests.iter().map(|t| t.desc.clone()).collect();
 
-    callback(TeFiltered(filtered_descs))?;
+    callback(TeFiltered(filtered_descs, shuffle_seed))?;
 
     let (filtered_tests, filtered_benchs): (Vec<_>, _) =
         filtered_tests.into_iter().partition(|e| match e.testfn {
@@ -1122,6 +1571,10 @@ where
     let (tx, rx) = channel::<MonitorMsg>();
 
     let mut running_tests: TestMap = HashMap::default();
+    // Deadlines after which a running test is failed, and the tests that
+    // were failed that way whose threads may still report back later.
+    let mut test_deadlines: TestMap = HashMap::default();
+    let mut timed_out_tests: HashSet<TestDesc> = HashSet::new();
 
     fn get_timed_out_tests(running_tests: &mut TestMap) -> Vec<TestDesc> {
         let now = Instant::now();
@@ -1152,33 +1605,72 @@ where
         })
     };
 
-    if concurrency == 1 {
+    // Tests with a time limit have to run on their own thread, even when
+    // running serially, so that the main thread can give up on them. Isolated
+    // tests instead enforce their time limit by killing the child process.
+    let has_time_limits = !opts.isolate
+        && remaining.iter().any(|t| test_time_limit(opts, &t.desc).is_some());
+
+    let mut retries = Retries::new(opts.retries);
+
+    if concurrency == 1 && !has_time_limits {
         while !remaining.is_empty() {
             let test = remaining.pop().unwrap();
-            callback(TeWait(test.desc.clone()))?;
+            if retries.start(&test) {
+                callback(TeWait(test.desc.clone()))?;
+            }
             run_test(opts, !opts.run_tests, test, tx.clone(), Concurrent::No);
             let (test, result, stdout) = rx.recv().unwrap();
-            callback(TeResult(test, result, stdout))?;
+            match retries.finish(test, result, stdout) {
+                RetryOutcome::Retry(test) => remaining.push(test),
+                RetryOutcome::Report(test, result, stdout) => {
+                    callback(TeResult(test, result, stdout))?
+                }
+            }
         }
     } else {
         while pending > 0 || !remaining.is_empty() {
             while pending < concurrency && !remaining.is_empty() {
                 let test = remaining.pop().unwrap();
-                let timeout = Instant::now() + Duration::from_secs(TEST_WARN_TIMEOUT_S);
+                let now = Instant::now();
+                let timeout = now + Duration::from_secs(TEST_WARN_TIMEOUT_S);
                 running_tests.insert(test.desc.clone(), timeout);
-                callback(TeWait(test.desc.clone()))?; //here no pad
+                let time_limit = if opts.isolate {
+                    None
+                } else {
+                    test_time_limit(opts, &test.desc)
+                };
+                if let Some(secs) = time_limit {
+                    test_deadlines.insert(test.desc.clone(), now + Duration::from_secs(secs));
+                }
+                if retries.start(&test) {
+                    callback(TeWait(test.desc.clone()))?; //here no pad
+                }
                 run_test(opts, !opts.run_tests, test, tx.clone(), Concurrent::Yes);
                 pending += 1;
             }
 
             let mut res;
             loop {
-                if let Some(timeout) = calc_timeout(&running_tests) {
+                let timeout = match (calc_timeout(&running_tests), calc_timeout(&test_deadlines)) {
+                    (Some(warn), Some(fail)) => Some(cmp::min(warn, fail)),
+                    (warn, fail) => warn.or(fail),
+                };
+                if let Some(timeout) = timeout {
                     res = rx.recv_timeout(timeout);
                     for test in get_timed_out_tests(&mut running_tests) {
                         callback(TeTimeout(test))?;
                     }
-                    if res != Err(RecvTimeoutError::Timeout) {
+                    let hung_tests = get_timed_out_tests(&mut test_deadlines);
+                    let any_hung = !hung_tests.is_empty();
+                    for test in hung_tests {
+                        running_tests.remove(&test);
+                        timed_out_tests.insert(test.clone());
+                        let stdout = retries.abandon(&test);
+                        callback(TeResult(test, TrTimedFail, stdout))?;
+                        pending -= 1;
+                    }
+                    if res != Err(RecvTimeoutError::Timeout) || any_hung {
                         break;
                     }
                 } else {
@@ -1187,10 +1679,24 @@ where
                 }
             }
 
-            let (desc, result, stdout) = res.unwrap();
+            let (desc, result, stdout) = match res {
+                // Only hung tests were reported, go start some new ones.
+                Err(RecvTimeoutError::Timeout) => continue,
+                res => res.unwrap(),
+            };
+            if timed_out_tests.remove(&desc) {
+                // This test has already been reported as failed.
+                continue;
+            }
             running_tests.remove(&desc);
+            test_deadlines.remove(&desc);
 
-            callback(TeResult(desc, result, stdout))?;
+            match retries.finish(desc, result, stdout) {
+                RetryOutcome::Retry(test) => remaining.push(test),
+                RetryOutcome::Report(desc, result, stdout) => {
+                    callback(TeResult(desc, result, stdout))?
+                }
+            }
             pending -= 1;
         }
     }
@@ -1207,6 +1713,122 @@ where
     Ok(())
 }
 
+/// Keeps track of the tests that are run again after failing, for `--retries`.
+struct Retries {
+    max_retries: usize,
+    /// The functions of the tests that can be retried, by test name
+    test_fns: HashMap<String, fn()>,
+    /// The number of failed attempts of each test that failed so far, and the
+    /// output of its first failure
+    failures: HashMap<String, (usize, Vec<u8>)>,
+}
+
+/// Appended to the output of a failed test that cannot be run again.
+const NOT_RETRYABLE_NOTE: &str =
+    "note: this test was not retried, only tests defined with #[test] can be run again\n";
+/// Appended to the output of a hung test, whose thread could still report
+/// back at any time.
+const HUNG_NOT_RETRIED_NOTE: &str =
+    "note: this test was not retried, since it may still be running\n";
+
+enum RetryOutcome {
+    Retry(TestDescAndFn),
+    Report(TestDesc, TestResult, Vec<u8>),
+}
+
+impl Retries {
+    fn new(max_retries: usize) -> Retries {
+        Retries {
+            max_retries,
+            test_fns: HashMap::new(),
+            failures: HashMap::new(),
+        }
+    }
+
+    /// Called before a test is run. Returns whether this is its first attempt.
+    fn start(&mut self, test: &TestDescAndFn) -> bool {
+        let name = test.desc.name.as_slice();
+        if self.failures.contains_key(name) {
+            return false;
+        }
+        if self.max_retries > 0 {
+            if let StaticTestFn(f) = test.testfn {
+                self.test_fns.insert(name.to_owned(), f);
+            }
+        }
+        true
+    }
+
+    /// Called when a test finished. Either returns the test to run again, or
+    /// the result to report for it.
+    fn finish(&mut self, desc: TestDesc, result: TestResult, stdout: Vec<u8>) -> RetryOutcome {
+        let name = desc.name.as_slice().to_owned();
+        let failed = match result {
+            TrFailed | TrFailedMsg(_) | TrTimedFail => true,
+            _ => false,
+        };
+
+        if !failed {
+            self.test_fns.remove(&name);
+            return match self.failures.remove(&name) {
+                Some((_, first_stdout)) if result == TrOk => {
+                    RetryOutcome::Report(desc, TrFlaky, first_stdout)
+                }
+                _ => RetryOutcome::Report(desc, result, stdout),
+            };
+        }
+
+        let attempts = self.failures.get(&name).map_or(0, |&(attempts, _)| attempts);
+        let test_fn = self.test_fns.get(&name).cloned();
+        match test_fn {
+            Some(f) if attempts < self.max_retries => {
+                self.failures
+                    .entry(name)
+                    .or_insert_with(|| (0, stdout))
+                    .0 += 1;
+                RetryOutcome::Retry(TestDescAndFn {
+                    desc,
+                    testfn: StaticTestFn(f),
+                })
+            }
+            _ => {
+                self.test_fns.remove(&name);
+                let retried = self.failures.remove(&name).is_some();
+                let mut stdout = stdout;
+                if self.max_retries > 0 && !retried {
+                    stdout.extend_from_slice(NOT_RETRYABLE_NOTE.as_bytes());
+                }
+                RetryOutcome::Report(desc, result, stdout)
+            }
+        }
+    }
+
+    /// Called when a test is failed because it hung. Such a test is never
+    /// retried; returns the output to report for it.
+    fn abandon(&mut self, desc: &TestDesc) -> Vec<u8> {
+        let name = desc.name.as_slice();
+        self.test_fns.remove(name);
+        self.failures.remove(name);
+        if self.max_retries > 0 {
+            HUNG_NOT_RETRIED_NOTE.as_bytes().to_vec()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// The number of seconds `desc` may run before it is failed, if any.
+#[cfg(not(stage0))]
+fn test_time_limit(opts: &TestOpts, desc: &TestDesc) -> Option<u64> {
+    desc.timeout.or(opts.test_timeout)
+}
+
+/// The number of seconds `desc` may run before it is failed, if any.
+#[cfg(stage0)]
+fn test_time_limit(opts: &TestOpts, _desc: &TestDesc) -> Option<u64> {
+    opts.test_timeout
+}
+
 #[allow(deprecated)]
 fn get_concurrency() -> usize {
     return match env::var("RUST_TEST_THREADS") {
@@ -1356,6 +1978,11 @@ pub fn filter_tests(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<TestDescA
         !opts.skip.iter().any(|sf| matches_filter(test, sf))
     });
 
+    // Only keep the tests of the selected shard
+    if let Some(shard) = opts.shard {
+        filtered.retain(|test| shard_of(test.desc.name.as_slice(), shard.count) == shard.index);
+    }
+
     // maybe unignore tests
     match opts.run_ignored {
         RunIgnored::Yes => {
@@ -1374,6 +2001,87 @@ pub fn filter_tests(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<TestDescA
     filtered
 }
 
+/// Returns the seed to shuffle the tests with, or `None` if the tests should
+/// run in their sorted order. When shuffling was requested without an
+/// explicit seed, one is derived from the current time.
+pub fn get_shuffle_seed(opts: &TestOpts) -> Option<u64> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    if let Some(seed) = opts.shuffle_seed {
+        return Some(seed);
+    }
+    if !opts.shuffle {
+        return None;
+    }
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::new(0, 0));
+    Some(ns_from_dur(since_epoch))
+}
+
+/// Deterministically permutes `tests` according to `seed`.
+///
+/// The permutation only depends on the seed and on the names of the tests,
+/// so the same seed reproduces the same order for the same (filtered) set of
+/// tests on every machine, whichever compiler built the runner.
+pub fn shuffle_tests(seed: u64, tests: &mut [TestDescAndFn]) {
+    // Sort first so that the result does not depend on the input order.
+    tests.sort_by(|t1, t2| t1.desc.name.as_slice().cmp(t2.desc.name.as_slice()));
+
+    let mut names_hash = FNV_OFFSET_BASIS;
+    for test in tests.iter() {
+        names_hash = fnv1a(names_hash, test.desc.name.as_slice().as_bytes());
+        // Separates the names, so that `ab, c` and `a, bc` hash differently.
+        names_hash = fnv1a(names_hash, &[0xff]);
+    }
+
+    // Fisher-Yates, drawing random numbers from a hash of the seed, the test
+    // names and a counter.
+    let mut counter = 0u64;
+    let mut next_u64 = || {
+        let mut hash = FNV_OFFSET_BASIS;
+        for &n in &[seed, names_hash, counter] {
+            hash = fnv1a(hash, &le_bytes(n));
+        }
+        counter += 1;
+        hash
+    };
+    for i in (1..tests.len()).rev() {
+        let j = (next_u64() % (i as u64 + 1)) as usize;
+        tests.swap(i, j);
+    }
+}
+
+/// The shard out of `count` that the test called `name` belongs to.
+fn shard_of(name: &str, count: usize) -> usize {
+    (fnv1a(FNV_OFFSET_BASIS, name.as_bytes()) % count as u64) as usize
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+
+/// Continues the 64-bit FNV-1a hash `hash` with `bytes`.
+///
+/// The shuffling and sharding of tests use FNV-1a rather than `DefaultHasher`,
+/// whose algorithm may change between releases, so that runners built by
+/// different compilers agree on the order and on the split of the tests.
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// The bytes of `n` in little-endian order, whatever the endianness of the
+/// machine.
+fn le_bytes(n: u64) -> [u8; 8] {
+    let mut bytes = [0; 8];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = (n >> (8 * i)) as u8;
+    }
+    bytes
+}
+
 pub fn convert_benchmarks_to_tests(tests: Vec<TestDescAndFn>) -> Vec<TestDescAndFn> {
     // convert benchmarks to tests, if we're not benchmarking them
     tests
@@ -1472,6 +2180,16 @@ pub fn run_test(
                 (benchfn.clone())(harness)
             });
         }
+        DynTestFn(_) | StaticTestFn(_) if opts.isolate => {
+            let time_limit = test_time_limit(opts, &desc).map(Duration::from_secs);
+            isolation::run_test_in_child_process(
+                desc,
+                monitor_ch,
+                opts.nocapture,
+                time_limit,
+                concurrency,
+            )
+        }
         DynTestFn(f) => {
             let cb = move || __rust_begin_short_backtrace(f);
             run_test_inner(desc, monitor_ch, opts.nocapture, Box::new(cb), concurrency)
@@ -1582,7 +2300,9 @@ impl Bencher {
             return;
         }
 
-        self.summary = Some(iter(&mut inner));
+        let (summary, samples) = iter_samples(&mut inner);
+        self.summary = Some(summary);
+        self.samples = samples;
     }
 
     pub fn bench<F>(&mut self, mut f: F) -> Option<stats::Summary>
@@ -1610,6 +2330,15 @@ where
 }
 
 pub fn iter<T, F>(inner: &mut F) -> stats::Summary
+where
+    F: FnMut() -> T,
+{
+    iter_samples(inner).0
+}
+
+/// Like `iter`, but also returns the (winsorized) ns/iter samples the summary
+/// was computed from.
+fn iter_samples<T, F>(inner: &mut F) -> (stats::Summary, Vec<f64>)
 where
     F: FnMut() -> T,
 {
@@ -1655,13 +2384,13 @@ where
         if loop_run > Duration::from_millis(100) && summ.median_abs_dev_pct < 1.0
             && summ.median - summ5.median < summ5.median_abs_dev
         {
-            return summ5;
+            return (summ5, samples.to_vec());
         }
 
         total_run = total_run + loop_run;
         // Longest we ever run for is 3s.
         if total_run > Duration::from_secs(3) {
-            return summ5;
+            return (summ5, samples.to_vec());
         }
 
         // If we overflow here just return the results so far. We check a
@@ -1671,7 +2400,7 @@ where
         n = match n.checked_mul(10) {
             Some(_) => n * 2,
             None => {
-                return summ5;
+                return (summ5, samples.to_vec());
             }
         };
     }
@@ -1692,6 +2421,7 @@ pub mod bench {
         let mut bs = Bencher {
             mode: BenchMode::Auto,
             summary: None,
+            samples: Vec::new(),
             bytes: 0,
         };
 
@@ -1722,7 +2452,9 @@ pub mod bench {
 
                 let bs = BenchSamples {
                     ns_iter_summ,
+                    ns_iter_samples: bs.samples.clone(),
                     mb_s: mb_s as usize,
+                    comparison: None,
                 };
                 TestResult::TrBench(bs)
             }
@@ -1732,7 +2464,9 @@ pub mod bench {
                 let samples: &mut [f64] = &mut [0.0_f64; 1];
                 let bs = BenchSamples {
                     ns_iter_summ: stats::Summary::new(samples),
+                    ns_iter_samples: Vec::new(),
                     mb_s: 0,
+                    comparison: None,
                 };
                 TestResult::TrBench(bs)
             }
@@ -1750,6 +2484,7 @@ pub mod bench {
         let mut bs = Bencher {
             mode: BenchMode::Single,
             summary: None,
+            samples: Vec::new(),
             bytes: 0,
         };
         bs.bench(f);
@@ -1759,8 +2494,10 @@ pub mod bench {
 #[cfg(test)]
 mod tests {
     use test::{filter_tests, parse_opts, run_test, DynTestFn, DynTestName, MetricMap, RunIgnored,
-               ShouldPanic, StaticTestName, TestDesc, TestDescAndFn, TestOpts, TrFailed,
-               TrFailedMsg, TrIgnored, TrOk};
+               ShouldPanic, StaticTestFn, StaticTestName, TestDesc, TestDescAndFn, TestOpts,
+               TrFailed, TrFailedMsg, TrFlaky, TrIgnored, TrOk, TrTimedFail};
+    use {get_shuffle_seed, run_tests, shuffle_tests, Shard};
+    use TestEvent::TeResult;
     use std::sync::mpsc::channel;
     use bench;
     use Bencher;
@@ -1775,6 +2512,8 @@ mod tests {
                     ignore: true,
                     should_panic: ShouldPanic::No,
                     allow_fail: false,
+                    #[cfg(not(stage0))]
+                    timeout: None,
                 },
                 testfn: DynTestFn(Box::new(move || {})),
             },
@@ -1784,6 +2523,8 @@ mod tests {
                     ignore: false,
                     should_panic: ShouldPanic::No,
                     allow_fail: false,
+                    #[cfg(not(stage0))]
+                    timeout: None,
                 },
                 testfn: DynTestFn(Box::new(move || {})),
             },
@@ -1801,6 +2542,8 @@ mod tests {
                 ignore: true,
                 should_panic: ShouldPanic::No,
                 allow_fail: false,
+                #[cfg(not(stage0))]
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(f)),
         };
@@ -1819,6 +2562,8 @@ mod tests {
                 ignore: true,
                 should_panic: ShouldPanic::No,
                 allow_fail: false,
+                #[cfg(not(stage0))]
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(f)),
         };
@@ -1839,6 +2584,8 @@ mod tests {
                 ignore: false,
                 should_panic: ShouldPanic::Yes,
                 allow_fail: false,
+                #[cfg(not(stage0))]
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(f)),
         };
@@ -1859,6 +2606,8 @@ mod tests {
                 ignore: false,
                 should_panic: ShouldPanic::YesWithMessage("error message"),
                 allow_fail: false,
+                #[cfg(not(stage0))]
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(f)),
         };
@@ -1881,6 +2630,8 @@ mod tests {
                 ignore: false,
                 should_panic: ShouldPanic::YesWithMessage(expected),
                 allow_fail: false,
+                #[cfg(not(stage0))]
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(f)),
         };
@@ -1899,6 +2650,8 @@ mod tests {
                 ignore: false,
                 should_panic: ShouldPanic::Yes,
                 allow_fail: false,
+                #[cfg(not(stage0))]
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(f)),
         };
@@ -1908,6 +2661,129 @@ mod tests {
         assert!(res == TrFailed);
     }
 
+    #[test]
+    #[cfg(not(stage0))]
+    fn test_hung_test_times_out() {
+        fn f() {
+            ::std::thread::sleep(::std::time::Duration::from_secs(30));
+        }
+        let desc = TestDescAndFn {
+            desc: TestDesc {
+                name: StaticTestName("whatever"),
+                ignore: false,
+                should_panic: ShouldPanic::No,
+                allow_fail: false,
+                timeout: Some(1),
+            },
+            testfn: DynTestFn(Box::new(f)),
+        };
+        let mut opts = TestOpts::new();
+        opts.run_tests = true;
+        opts.test_threads = Some(1);
+        let mut results = Vec::new();
+        run_tests(&opts, vec![desc], |event| {
+            if let TeResult(_, result, _) = event {
+                results.push(result);
+            }
+            Ok(())
+        }).unwrap();
+        assert!(results == vec![TrTimedFail]);
+    }
+
+    #[test]
+    fn parse_isolate_flag() {
+        let args = vec![
+            "progname".to_string(),
+            "-Zunstable-options".to_string(),
+            "--isolate".to_string(),
+        ];
+        let opts = parse_opts(&args).unwrap().unwrap();
+        assert!(opts.isolate);
+    }
+
+    #[test]
+    fn parse_retries_flag() {
+        let args = vec![
+            "progname".to_string(),
+            "-Zunstable-options".to_string(),
+            "--retries".to_string(),
+            "3".to_string(),
+        ];
+        let opts = parse_opts(&args).unwrap().unwrap();
+        assert_eq!(opts.retries, 3);
+
+        let args = vec!["progname".to_string(), "--retries".to_string(), "3".to_string()];
+        assert!(parse_opts(&args).unwrap().is_err());
+    }
+
+    #[test]
+    fn retried_tests_are_reported_as_flaky() {
+        use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+
+        static FLAKY_RUNS: AtomicUsize = ATOMIC_USIZE_INIT;
+        fn flaky() {
+            assert!(FLAKY_RUNS.fetch_add(1, Ordering::SeqCst) >= 2);
+        }
+        fn broken() {
+            panic!();
+        }
+        fn desc(name: &'static str, f: fn()) -> TestDescAndFn {
+            TestDescAndFn {
+                desc: TestDesc {
+                    name: StaticTestName(name),
+                    ignore: false,
+                    should_panic: ShouldPanic::No,
+                    allow_fail: false,
+                    #[cfg(not(stage0))]
+                    timeout: None,
+                },
+                testfn: StaticTestFn(f),
+            }
+        }
+
+        let mut opts = TestOpts::new();
+        opts.run_tests = true;
+        opts.retries = 2;
+        let mut results = Vec::new();
+        run_tests(&opts, vec![desc("flaky", flaky), desc("broken", broken)], |event| {
+            if let TeResult(desc, result, _) = event {
+                results.push((desc.name.to_string(), result));
+            }
+            Ok(())
+        }).unwrap();
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+        assert!(results == vec![("broken".to_string(), TrFailed), ("flaky".to_string(), TrFlaky)]);
+    }
+
+    #[test]
+    fn dyn_tests_are_reported_as_not_retried() {
+        let test = TestDescAndFn {
+            desc: TestDesc {
+                name: StaticTestName("dyn"),
+                ignore: false,
+                should_panic: ShouldPanic::No,
+                allow_fail: false,
+                #[cfg(not(stage0))]
+                timeout: None,
+            },
+            testfn: DynTestFn(Box::new(move || panic!())),
+        };
+
+        let mut opts = TestOpts::new();
+        opts.run_tests = true;
+        opts.retries = 2;
+        let mut results = Vec::new();
+        run_tests(&opts, vec![test], |event| {
+            if let TeResult(_, result, stdout) = event {
+                results.push((result, String::from_utf8(stdout).unwrap()));
+            }
+            Ok(())
+        }).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].0 == TrFailed);
+        assert!(results[0].1.ends_with(super::NOT_RETRYABLE_NOTE));
+    }
+
     #[test]
     fn parse_ignored_flag() {
         let args = vec![
@@ -1976,6 +2852,8 @@ mod tests {
                         ignore: false,
                         should_panic: ShouldPanic::No,
                         allow_fail: false,
+                        #[cfg(not(stage0))]
+                        timeout: None,
                     },
                     testfn: DynTestFn(Box::new(move || {})),
                 })
@@ -2059,6 +2937,54 @@ mod tests {
         assert_eq!(exact.len(), 1);
     }
 
+    #[test]
+    fn parse_shard_flags() {
+        let args = |extra: &[&str]| {
+            let mut args = vec!["progname".to_string(), "-Zunstable-options".to_string()];
+            args.extend(extra.iter().map(|s| s.to_string()));
+            args
+        };
+        let opts = parse_opts(&args(&["--shard-index=1", "--shard-count=3"])).unwrap().unwrap();
+        assert_eq!(opts.shard, Some(Shard { index: 1, count: 3 }));
+        assert!(parse_opts(&args(&["--shard-index=1"])).unwrap().is_err());
+        assert!(parse_opts(&args(&["--shard-index=3", "--shard-count=3"])).unwrap().is_err());
+        assert!(parse_opts(&args(&["--shard-index=0", "--shard-count=0"])).unwrap().is_err());
+    }
+
+    #[test]
+    fn shards_partition_tests() {
+        fn tests() -> Vec<TestDescAndFn> {
+            (0..100)
+                .map(|i| TestDescAndFn {
+                    desc: TestDesc {
+                        name: DynTestName(format!("module::test{}", i)),
+                        ignore: false,
+                        should_panic: ShouldPanic::No,
+                        allow_fail: false,
+                        #[cfg(not(stage0))]
+                        timeout: None,
+                    },
+                    testfn: DynTestFn(Box::new(move || {})),
+                })
+                .collect()
+        }
+
+        let mut all = Vec::new();
+        for index in 0..4 {
+            let opts = TestOpts {
+                shard: Some(Shard { index, count: 4 }),
+                ..TestOpts::new()
+            };
+            let shard = filter_tests(&opts, tests());
+            assert!(!shard.is_empty());
+            all.extend(shard.into_iter().map(|t| t.desc.name.to_string()));
+        }
+        all.sort();
+        let mut expected = tests().into_iter().map(|t| t.desc.name.to_string()).collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(all, expected);
+    }
+
     #[test]
     pub fn sort_tests() {
         let mut opts = TestOpts::new();
@@ -2087,6 +3013,8 @@ mod tests {
                         ignore: false,
                         should_panic: ShouldPanic::No,
                         allow_fail: false,
+                        #[cfg(not(stage0))]
+                        timeout: None,
                     },
                     testfn: DynTestFn(Box::new(testfn)),
                 };
@@ -2115,6 +3043,63 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_shuffle_seed_flag() {
+        let args = vec![
+            "progname".to_string(),
+            "-Zunstable-options".to_string(),
+            "--shuffle-seed=42".to_string(),
+        ];
+        let opts = parse_opts(&args).unwrap().unwrap();
+        assert_eq!(opts.shuffle_seed, Some(42));
+        assert_eq!(get_shuffle_seed(&opts), Some(42));
+    }
+
+    #[test]
+    fn shuffle_requires_unstable_options() {
+        let args = vec!["progname".to_string(), "--shuffle".to_string()];
+        assert!(parse_opts(&args).unwrap().is_err());
+    }
+
+    #[test]
+    fn shuffle_tests_is_deterministic() {
+        fn tests() -> Vec<TestDescAndFn> {
+            (0..20)
+                .map(|i| TestDescAndFn {
+                    desc: TestDesc {
+                        name: DynTestName(format!("test{}", i)),
+                        ignore: false,
+                        should_panic: ShouldPanic::No,
+                        allow_fail: false,
+                        #[cfg(not(stage0))]
+                        timeout: None,
+                    },
+                    testfn: DynTestFn(Box::new(move || {})),
+                })
+                .collect()
+        }
+        fn names(tests: &[TestDescAndFn]) -> Vec<String> {
+            tests.iter().map(|t| t.desc.name.to_string()).collect()
+        }
+
+        let mut a = tests();
+        shuffle_tests(1, &mut a);
+        let mut b = tests();
+        b.reverse();
+        shuffle_tests(1, &mut b);
+        assert_eq!(names(&a), names(&b));
+
+        let mut c = tests();
+        shuffle_tests(2, &mut c);
+        assert!(names(&a) != names(&c));
+
+        let mut sorted = names(&a);
+        sorted.sort();
+        let mut expected = names(&tests());
+        expected.sort();
+        assert_eq!(sorted, expected);
+    }
+
     #[test]
     pub fn test_metricmap_compare() {
         let mut m1 = MetricMap::new();
@@ -2163,6 +3148,8 @@ mod tests {
             ignore: false,
             should_panic: ShouldPanic::No,
             allow_fail: false,
+            #[cfg(not(stage0))]
+            timeout: None,
         };
 
         ::bench::benchmark(desc, tx, true, f);
@@ -2182,6 +3169,8 @@ mod tests {
             ignore: false,
             should_panic: ShouldPanic::No,
             allow_fail: false,
+            #[cfg(not(stage0))]
+            timeout: None,
         };
 
         ::bench::benchmark(desc, tx, true, f);
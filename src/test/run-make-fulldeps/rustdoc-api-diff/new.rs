@@ -0,0 +1,28 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+pub struct Foo {
+    pub a: u32,
+    pub b: u32,
+}
+
+pub trait Tr {
+    fn method(&self);
+    fn provided(&self) {}
+    fn required(&self);
+}
+
+pub fn added() {}
+
+pub fn changed(_: u64) {}
+
+pub fn renamed(y: u32) {}
+
+pub fn generic<'b, U: Clone>(u: &'b U) {}
@@ -0,0 +1,31 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_name = "foo"]
+
+/// Links to [`Baz::run`], to [the `bar` module](../foo/bar/index.html), to
+/// [a missing struct](struct.Missing.html), to [a missing method][missing] and to
+/// [the examples](#examples).
+///
+/// [`Baz::run`]: bar/struct.Baz.html#method.run
+/// [missing]: bar/struct.Baz.html#method.missing
+///
+/// # Examples
+///
+/// Links to [nowhere](#nowhere) and to [a website](https://example.com).
+pub fn foo() {}
+
+pub mod bar {
+    pub struct Baz;
+
+    impl Baz {
+        pub fn run(&self) {}
+    }
+}
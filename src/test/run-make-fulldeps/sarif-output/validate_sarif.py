@@ -0,0 +1,74 @@
+#!/usr/bin/env python
+
+# Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+# file at the top-level directory of this distribution and at
+# http://rust-lang.org/COPYRIGHT.
+#
+# Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+# http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+# <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+# option. This file may not be copied, modified, or distributed
+# except according to those terms.
+
+import sys
+import json
+
+kind = sys.argv[1]
+
+if kind == "rustdoc":
+    # rustdoc would write one log per diagnostic handler, so it rejects the format instead of
+    # writing several concatenated documents
+    with open(sys.argv[2]) as f:
+        output = f.read()
+    assert "`--error-format sarif` is not supported by rustdoc yet" in output
+    assert "\"version\"" not in output
+    sys.exit(0)
+
+# The whole output must be a single JSON document
+with open(sys.argv[2]) as f:
+    log = json.load(f)
+
+assert log["version"] == "2.1.0"
+assert len(log["runs"]) == 1
+run = log["runs"][0]
+assert run["tool"]["driver"]["name"] == "rustc"
+invocation = run["invocations"][0]
+rules = dict((rule["id"], rule) for rule in run["tool"]["driver"]["rules"])
+results = dict((result["ruleId"], result) for result in run["results"])
+
+if kind == "early-warning":
+    # The warnings given while parsing the options end up in the same log
+    notifications = invocation["toolExecutionNotifications"]
+    assert any("resetting to default -C codegen-units=1" in n["message"]["text"]
+               for n in notifications)
+elif kind == "warning":
+    assert invocation["executionSuccessful"]
+    assert "unused_variables" in rules
+
+    warning = results["unused_variables"]
+    assert warning["level"] == "warning"
+    location = warning["locations"][0]["physicalLocation"]
+    assert location["artifactLocation"]["uri"] == "warning.rs"
+    assert location["region"]["startLine"] == 12
+    assert location["region"]["startColumn"] == 9
+
+    fix = warning["fixes"][0]
+    assert fix["properties"]["applicability"] == "MachineApplicable"
+    replacement = fix["artifactChanges"][0]["replacements"][0]
+    assert replacement["insertedContent"]["text"] == "_x"
+    assert replacement["deletedRegion"]["startLine"] == 12
+else:
+    assert not invocation["executionSuccessful"]
+    assert "fullDescription" in rules["E0308"]
+    assert "helpUri" in rules["E0308"]
+
+    error = results["E0308"]
+    assert error["level"] == "error"
+    location = error["locations"][0]["physicalLocation"]
+    assert location["artifactLocation"]["uri"] == "error.rs"
+    assert location["region"]["startLine"] == 12
+    assert location["region"]["startColumn"] == 19
+
+    # "aborting due to previous error" is not about the code
+    notifications = invocation["toolExecutionNotifications"]
+    assert any("aborting" in n["message"]["text"] for n in notifications)
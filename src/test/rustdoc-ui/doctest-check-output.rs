@@ -0,0 +1,55 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags:--test
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+// failure-status: 101
+// rustc-env:RUST_BACKTRACE=0
+
+/// ```check_output
+/// println!("Hello, {}!", "world");
+/// ```
+///
+/// ```text
+/// Hello, world!
+/// ```
+pub struct Good;
+
+/// ```check_output
+/// println!("{}", 1 + 1);
+/// ```
+///
+/// ```text
+/// 3
+/// ```
+pub struct Wrong;
+
+/// ```check_output
+/// println!("no expected output");
+/// ```
+pub struct Missing;
+
+/// ```check_output,no_run
+/// println!("never run");
+/// ```
+///
+/// ```text
+/// never run
+/// ```
+pub struct NoRun;
+
+/// ```check_output,ignore
+/// println!("ignored");
+/// ```
+///
+/// ```text
+/// ignored
+/// ```
+pub struct Ignored;
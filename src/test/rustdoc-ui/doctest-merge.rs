@@ -0,0 +1,48 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check which doctests `--merge-doctests` runs from the merged test harness. The harness runs
+// each of its tests with `--exact`, while a doctest compiled on its own gets no arguments.
+
+// compile-flags:--test -Z unstable-options --merge-doctests
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+
+/// ```
+/// assert!(std::env::args().any(|arg| arg == "--exact"));
+/// ```
+pub struct Merged;
+
+/// ```should_panic
+/// if std::env::args().any(|arg| arg == "--exact") {
+///     panic!("run from the merged harness");
+/// }
+/// ```
+pub struct MergedShouldPanic;
+
+/// ```
+/// fn main() {
+///     assert!(!std::env::args().any(|arg| arg == "--exact"));
+/// }
+/// ```
+pub struct HasMain;
+
+/// ```
+/// #![allow(dead_code)]
+/// assert!(!std::env::args().any(|arg| arg == "--exact"));
+/// ```
+pub struct HasCrateAttribute;
+
+// The 2018 harness doesn't compile, since the harness has no `main` function, so its tests are
+// compiled on their own instead.
+/// ```edition2018
+/// let _: fn() = main;
+/// assert!(!std::env::args().any(|arg| arg == "--exact"));
+/// ```
+pub struct HarnessFailsToCompile;
@@ -0,0 +1,41 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_name = "foo"]
+
+macro_rules! make_struct {
+    ($name:ident) => {
+        /// A generated struct.
+        pub struct $name;
+    }
+}
+
+macro_rules! make_inner {
+    () => {
+        make_struct!(Inner);
+    }
+}
+
+// @has foo/struct.Generated.html
+// @has - '//*[@class="stab macro-expansion"]' \
+//      'Generated by make_struct! at macro-expansion-provenance.rs:31'
+// @!has - '//*[@class="stab macro-expansion"]' 'src/test/rustdoc'
+// @has - '//a[@class="srclink"]/@href' '../src/foo/macro-expansion-provenance.rs.html#31'
+make_struct!(Generated);
+
+// @has foo/struct.Inner.html
+// @has - '//*[@class="stab macro-expansion"]' 'Generated by make_struct! at'
+// @has - '//*[@class="stab macro-expansion"]' 'in the expansion of make_inner! at'
+// @has - '//a[@class="srclink"]/@href' '../src/foo/macro-expansion-provenance.rs.html#37'
+make_inner!();
+
+// @has foo/struct.Plain.html
+// @!has - '//*[@class="stab macro-expansion"]'
+pub struct Plain;
@@ -0,0 +1,48 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_name = "foo"]
+#![feature(doc_cfg)]
+#![allow(deprecated)]
+
+// `null` is minified to `N` in the search index.
+// @matches 'search-index.js' '"old","[^"]*","Old function\.",(?:null|N),\[\[\]\],1\]'
+/// Old function.
+#[deprecated(since = "1.0.0", note = "use `current` instead")]
+pub fn old() {}
+
+// @matches - '"unix_only","[^"]*","Unix only\.",(?:null|N),\[\[\]\],0,"Unix"\]'
+/// Unix only.
+#[doc(cfg(unix))]
+pub fn unix_only() {}
+
+// @matches - '"current","[^"]*","Current function\.",(?:null|N),\[\[\]\]\]'
+/// Current function.
+pub fn current() {}
+
+pub mod inner {
+    #[deprecated(since = "1.0.0")]
+    pub struct Gone;
+}
+
+// @has foo/index.html '//tr[td/code[contains(., "inner::Gone")]]//*[@class="stab deprecated"]' \
+//      'Deprecated'
+#[doc(no_inline)]
+pub use inner::Gone;
+
+pub mod platform {
+    #[doc(cfg(unix))]
+    pub struct UnixOnly;
+}
+
+// @has foo/index.html '//tr[td/code[contains(., "::UnixOnly")]]//*[@class="stab portability"]' \
+//      'Unix'
+#[doc(no_inline)]
+pub use platform::UnixOnly;
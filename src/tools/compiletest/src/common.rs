@@ -122,7 +122,8 @@ impl CompareMode {
 
 #[derive(Clone)]
 pub struct Config {
-    /// Whether to overwrite stderr/stdout files instead of complaining about changes in output
+    /// Whether to overwrite expected output files (ui stderr/stdout, mir-opt `// END` blocks and
+    /// pretty-printer output) instead of complaining about changes in output
     pub bless: bool,
 
     /// The library paths required for running the compiler
@@ -241,6 +242,13 @@ pub struct Config {
     /// where to find the remote test client process, if we're using it
     pub remote_test_client: Option<PathBuf>,
 
+    /// A program to run every test binary under, for running tests locally
+    /// under an emulator such as `qemu-user`
+    pub runner: Option<String>,
+
+    /// The arguments passed to `runner` before the test binary
+    pub runner_args: Vec<String>,
+
     /// mode describing what file the actual ui output will be compared to
     pub compare_mode: Option<CompareMode>,
 
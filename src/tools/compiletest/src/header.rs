@@ -58,6 +58,16 @@ impl Ignore {
     }
 }
 
+/// How far a test that must compile successfully is taken, as set by the
+/// `check-pass` and `build-pass` directives.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PassMode {
+    /// Stop after analysis, without any codegen.
+    Check,
+    /// Go through codegen and linking, but do not run the result.
+    Build,
+}
+
 /// The result of parse_cfg_name_directive.
 #[derive(Clone, Copy, PartialEq, Debug)]
 enum ParsedNameDirective {
@@ -332,6 +342,8 @@ pub struct TestProps {
     pub run_pass: bool,
     // Skip any codegen step and running the executable. Only for run-pass.
     pub skip_codegen: bool,
+    // Compile the test successfully up to the given point, without running it.
+    pub pass_mode: Option<PassMode>,
     // Do not pass `-Z ui-testing` to UI tests
     pub disable_ui_testing_normalization: bool,
     // customized normalization rules
@@ -369,6 +381,7 @@ impl TestProps {
             check_test_line_numbers_match: false,
             run_pass: false,
             skip_codegen: false,
+            pass_mode: None,
             disable_ui_testing_normalization: false,
             normalize_stdout: vec![],
             normalize_stderr: vec![],
@@ -489,9 +502,17 @@ impl TestProps {
                 self.run_pass = config.parse_run_pass(ln);
             }
 
+            if let Some(mode) = config.parse_pass_mode(ln) {
+                if self.pass_mode.map_or(false, |m| m != mode) {
+                    panic!("`check-pass` and `build-pass` cannot be used together");
+                }
+                self.pass_mode = Some(mode);
+            }
+
             if !self.compile_pass {
-                // run-pass implies must_compile_successfully
-                self.compile_pass = config.parse_compile_pass(ln) || self.run_pass;
+                // run-pass, check-pass and build-pass imply must_compile_successfully
+                self.compile_pass =
+                    config.parse_compile_pass(ln) || self.run_pass || self.pass_mode.is_some();
             }
 
             if !self.skip_codegen {
@@ -524,6 +545,10 @@ impl TestProps {
             }
         });
 
+        if self.pass_mode.is_some() && self.run_pass {
+            panic!("`run-pass` cannot be combined with `check-pass` or `build-pass`");
+        }
+
         if self.failure_status == -1 {
             self.failure_status = match config.mode {
                 Mode::RunFail => 101,
@@ -679,6 +704,16 @@ impl Config {
         self.parse_name_directive(line, "skip-codegen")
     }
 
+    fn parse_pass_mode(&self, line: &str) -> Option<PassMode> {
+        if self.parse_name_directive(line, "check-pass") {
+            Some(PassMode::Check)
+        } else if self.parse_name_directive(line, "build-pass") {
+            Some(PassMode::Build)
+        } else {
+            None
+        }
+    }
+
     fn parse_env(&self, line: &str, name: &str) -> Option<(String, String)> {
         self.parse_name_value_directive(line, name).map(|nv| {
             // nv is either FOO or FOO=BAR
@@ -35,6 +35,7 @@ use common::{Config, TestPaths};
 use common::{DebugInfoBoth, DebugInfoGdb, DebugInfoLldb, Mode, Pretty};
 use filetime::FileTime;
 use getopts::Options;
+use runtest::BlessedFile;
 use std::env;
 use std::ffi::OsString;
 use std::fs;
@@ -167,7 +168,7 @@ pub fn parse_config(args: Vec<String>) -> Config {
         .optflag(
             "",
             "bless",
-            "overwrite stderr/stdout files instead of complaining about a mismatch",
+            "overwrite expected output files instead of complaining about a mismatch",
         )
         .optflag(
             "",
@@ -235,6 +236,18 @@ pub fn parse_config(args: Vec<String>) -> Config {
             "path to the remote test client",
             "PATH",
         )
+        .optopt(
+            "",
+            "runner",
+            "program to run every test binary under, such as an emulator",
+            "PROGRAM",
+        )
+        .optmulti(
+            "",
+            "runner-arg",
+            "argument passed to the runner before the test binary, can be repeated",
+            "ARG",
+        )
         .optopt(
             "",
             "compare-mode",
@@ -342,6 +355,8 @@ pub fn parse_config(args: Vec<String>) -> Config {
         quiet: matches.opt_present("quiet"),
         color,
         remote_test_client: matches.opt_str("remote-test-client").map(PathBuf::from),
+        runner: matches.opt_str("runner"),
+        runner_args: matches.opt_strs("runner-arg"),
         compare_mode: matches.opt_str("compare-mode").map(CompareMode::parse),
 
         cc: matches.opt_str("cc").unwrap(),
@@ -379,6 +394,8 @@ pub fn log_config(config: &Config) {
     );
     logv(c, format!("filter_exact: {}", config.filter_exact));
     logv(c, format!("runtool: {}", opt_str(&config.runtool)));
+    logv(c, format!("runner: {}", opt_str(&config.runner)));
+    logv(c, format!("runner_args: {:?}", config.runner_args));
     logv(
         c,
         format!("host-rustcflags: {}", opt_str(&config.host_rustcflags)),
@@ -498,6 +515,9 @@ pub fn run_tests(config: &Config) {
     env::set_var("TARGET", &config.target);
 
     let res = test::run_tests_console(&opts, tests.into_iter().collect());
+    if config.bless {
+        print_blessed_files();
+    }
     match res {
         Ok(true) => {}
         Ok(false) => panic!("Some tests failed"),
@@ -507,6 +527,30 @@ pub fn run_tests(config: &Config) {
     }
 }
 
+fn print_blessed_files() {
+    print!("{}", blessed_files_summary(&runtest::take_blessed_files()));
+}
+
+fn blessed_files_summary(blessed: &[BlessedFile]) -> String {
+    if blessed.is_empty() {
+        return String::new();
+    }
+
+    let mut summary = format!("blessed {} expected output file(s):\n", blessed.len());
+    for file in blessed {
+        match *file {
+            BlessedFile::Updated(ref path) => {
+                summary.push_str(&format!("    updated {}\n", path.display()))
+            }
+            BlessedFile::Deleted(ref path) => {
+                summary.push_str(&format!("    deleted {}\n", path.display()))
+            }
+        }
+    }
+    summary.push('\n');
+    summary
+}
+
 pub fn test_opts(config: &Config) -> test::TestOpts {
     test::TestOpts {
         filter: config.filter.clone(),
@@ -531,6 +575,25 @@ pub fn test_opts(config: &Config) -> test::TestOpts {
         color: config.color,
         test_threads: None,
         skip: vec![],
+        // The options below are newer than the stage0 libtest that compiletest is built with.
+        #[cfg(not(stage0))]
+        shuffle: false,
+        #[cfg(not(stage0))]
+        shuffle_seed: None,
+        #[cfg(not(stage0))]
+        test_timeout: None,
+        #[cfg(not(stage0))]
+        isolate: false,
+        #[cfg(not(stage0))]
+        save_baseline: None,
+        #[cfg(not(stage0))]
+        baseline: None,
+        #[cfg(not(stage0))]
+        noise_threshold: test::DEFAULT_NOISE_THRESHOLD,
+        #[cfg(not(stage0))]
+        shard: None,
+        #[cfg(not(stage0))]
+        retries: 0,
         list: false,
         options: test::Options::new(),
     }
@@ -671,6 +734,9 @@ pub fn make_test(config: &Config, testpaths: &TestPaths) -> Vec<test::TestDescAn
                     ignore,
                     should_panic,
                     allow_fail: false,
+                    // The stage0 libtest has no time limits.
+                    #[cfg(not(stage0))]
+                    timeout: None,
                 },
                 testfn: make_test_closure(config, early_props.ignore, testpaths, revision),
             }
@@ -1043,3 +1109,45 @@ fn test_extract_gdb_version() {
         7012050: "GNU gdb (GDB) 7.12.50.20161027-git",
     }
 }
+
+#[test]
+fn test_blessed_files_summary() {
+    assert_eq!(blessed_files_summary(&[]), "");
+
+    let blessed = [
+        BlessedFile::Updated(PathBuf::from("a.stderr")),
+        BlessedFile::Deleted(PathBuf::from("b.stdout")),
+    ];
+    assert_eq!(
+        blessed_files_summary(&blessed),
+        "blessed 2 expected output file(s):\n    updated a.stderr\n    deleted b.stdout\n\n"
+    );
+}
+
+#[test]
+fn test_parse_runner() {
+    fn parse(extra: &[&str]) -> Config {
+        let mut args: Vec<String> = vec![
+            "compiletest", "--compile-lib-path", "lib", "--run-lib-path", "lib",
+            "--rustc-path", "rustc", "--lldb-python", "python", "--docck-python", "python",
+            "--src-base", "src/test/ui", "--build-base", "build", "--stage-id", "stage1-A",
+            "--mode", "ui", "--android-cross-path", "", "--cc", "cc", "--cxx", "c++",
+            "--cflags", "", "--llvm-components", "", "--llvm-cxxflags", "",
+        ].into_iter().map(String::from).collect();
+        args.extend(extra.iter().map(|s| s.to_string()));
+        parse_config(args)
+    }
+
+    let config = parse(&[]);
+    assert_eq!(config.runner, None);
+    assert!(config.runner_args.is_empty());
+
+    // Arguments are kept apart, even when they contain spaces.
+    let config = parse(&[
+        "--runner", "/opt/my emulator/qemu-arm",
+        "--runner-arg", "-L",
+        "--runner-arg", "/usr/arm linux",
+    ]);
+    assert_eq!(config.runner, Some("/opt/my emulator/qemu-arm".to_string()));
+    assert_eq!(config.runner_args, vec!["-L".to_string(), "/usr/arm linux".to_string()]);
+}
@@ -18,12 +18,13 @@ use common::{Incremental, MirOpt, RunMake, Ui};
 use diff;
 use errors::{self, Error, ErrorKind};
 use filetime::FileTime;
-use header::TestProps;
+use header::{PassMode, TestProps};
 use json;
 use regex::Regex;
 use rustfix::{apply_suggestions, get_suggestions_from_json, Filter};
 use util::{logv, PathBufExt};
 
+use std::cmp;
 use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::env;
@@ -33,16 +34,17 @@ use std::fs::{self, create_dir_all, File};
 use std::hash::{Hash, Hasher};
 use std::io::prelude::*;
 use std::io::{self, BufReader};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command, ExitStatus, Output, Stdio};
 use std::str;
+use std::sync::Mutex;
 
 use extract_gdb_version;
 use is_android_gdb_target;
 
 #[cfg(windows)]
 fn disable_error_reporting<F: FnOnce() -> R, R>(f: F) -> R {
-    use std::sync::Mutex;
     const SEM_NOGPFAULTERRORBOX: u32 = 0x0002;
     extern "system" {
         fn SetErrorMode(mode: u32) -> u32;
@@ -84,6 +86,36 @@ pub fn dylib_env_var() -> &'static str {
     }
 }
 
+/// An expected output file that was rewritten or deleted because of `--bless`.
+#[derive(Debug, PartialEq)]
+pub enum BlessedFile {
+    Updated(PathBuf),
+    Deleted(PathBuf),
+}
+
+impl BlessedFile {
+    pub fn path(&self) -> &Path {
+        match *self {
+            BlessedFile::Updated(ref path) | BlessedFile::Deleted(ref path) => path,
+        }
+    }
+}
+
+lazy_static! {
+    static ref BLESSED_FILES: Mutex<Vec<BlessedFile>> = Mutex::new(Vec::new());
+}
+
+fn record_blessed_file(file: BlessedFile) {
+    BLESSED_FILES.lock().unwrap().push(file);
+}
+
+/// Returns all expected output files blessed so far, sorted by path.
+pub fn take_blessed_files() -> Vec<BlessedFile> {
+    let mut files = ::std::mem::replace(&mut *BLESSED_FILES.lock().unwrap(), Vec::new());
+    files.sort_by(|a, b| a.path().cmp(b.path()));
+    files
+}
+
 #[derive(Debug, PartialEq)]
 pub enum DiffLine {
     Context(String),
@@ -291,7 +323,7 @@ impl<'test> TestCx<'test> {
             Ui => self.props.run_pass,
             _ => unimplemented!(),
         };
-        return run_pass && !self.props.skip_codegen;
+        return run_pass && !self.props.skip_codegen && self.props.pass_mode.is_none();
     }
 
     fn should_compile_successfully(&self) -> bool {
@@ -408,7 +440,7 @@ impl<'test> TestCx<'test> {
             "run-pass tests with expected warnings should be moved to ui/"
         );
 
-        if !self.props.skip_codegen {
+        if !self.props.skip_codegen && self.props.pass_mode.is_none() {
             let proc_res = self.exec_compiled_test();
             if !proc_res.status.success() {
                 self.fatal_proc_rec("test run failed!", &proc_res);
@@ -577,7 +609,28 @@ impl<'test> TestCx<'test> {
     }
 
     fn compare_source(&self, expected: &str, actual: &str) {
-        if expected != actual {
+        if expected == actual {
+            return;
+        }
+
+        let expected_file = pretty_expected_file(&self.testpaths.file, &self.props.pp_exact);
+        if self.config.bless && self.props.pp_exact.is_some() && expected_file.is_none() {
+            self.fatal(
+                "pretty-printed source does not match the test itself, \
+                 which `--bless` doesn't update; edit the test by hand, \
+                 or use `// pp-exact:<file>` to expect a separate file",
+            );
+        }
+        if let (true, Some(filepath)) = (self.config.bless, expected_file) {
+            if let Err(err) = fs::write(&filepath, actual) {
+                self.fatal(&format!(
+                    "failed to write pretty-printed source to `{}`: {}",
+                    filepath.display(),
+                    err,
+                ));
+            }
+            record_blessed_file(BlessedFile::Updated(filepath));
+        } else {
             self.fatal(&format!(
                 "pretty-printed source does not match expected source\n\
                  expected:\n\
@@ -1462,7 +1515,7 @@ impl<'test> TestCx<'test> {
             // `support-lib.so`. It will then execute the program on the
             // emulator with the arguments specified (in the environment we give
             // the process) and then report back the same result.
-            _ if self.config.remote_test_client.is_some() => {
+            _ if self.config.remote_test_client.is_some() && self.config.runner.is_none() => {
                 let aux_dir = self.aux_output_dir_name();
                 let ProcArgs { mut prog, args } = self.make_run_args();
                 if let Ok(entries) = aux_dir.read_dir() {
@@ -1778,7 +1831,7 @@ impl<'test> TestCx<'test> {
             }
         }
 
-        if self.props.skip_codegen {
+        if self.props.skip_codegen || self.props.pass_mode == Some(PassMode::Check) {
             assert!(
                 !self
                     .props
@@ -1854,6 +1907,14 @@ impl<'test> TestCx<'test> {
         // then split apart its command
         let mut args = self.split_maybe_args(&self.config.runtool);
 
+        // The runner (such as an emulator) comes after the tool, which runs
+        // on the host and can't be run by the runner. Its arguments are passed
+        // one by one, so that they may contain spaces.
+        if let Some(ref runner) = self.config.runner {
+            args.push(runner.clone());
+            args.extend(self.config.runner_args.iter().cloned());
+        }
+
         // If this is emscripten, then run tests under nodejs
         if self.config.target.contains("emscripten") {
             if let Some(ref p) = self.config.nodejs {
@@ -2812,32 +2873,93 @@ impl<'test> TestCx<'test> {
     fn check_mir_dump(&self) {
         let test_file_contents = fs::read_to_string(&self.testpaths.file).unwrap();
         if let Some(idx) = test_file_contents.find("// END RUST SOURCE") {
-            let (_, tests_text) = test_file_contents.split_at(idx + "// END_RUST SOURCE".len());
+            let (source_text, tests_text) =
+                test_file_contents.split_at(idx + "// END_RUST SOURCE".len());
             let tests_text_str = String::from(tests_text);
             let mut curr_test: Option<&str> = None;
             let mut curr_test_contents = vec![ExpectedLine::Elision];
+            // The lines of the test file after `// END RUST SOURCE`, with every
+            // mismatching block replaced by the actual MIR dump when blessing.
+            let mut blessed_lines = Vec::new();
+            let mut curr_block_lines = Vec::new();
+            let mut blessed_any = false;
             for l in tests_text_str.lines() {
                 debug!("line: {:?}", l);
                 if l.starts_with("// START ") {
                     let (_, t) = l.split_at("// START ".len());
                     curr_test = Some(t);
+                    blessed_lines.push(l.to_owned());
+                    curr_block_lines.clear();
+                    continue;
                 } else if l.starts_with("// END") {
                     let (_, t) = l.split_at("// END ".len());
                     if Some(t) != curr_test {
                         panic!("mismatched START END test name");
                     }
-                    self.compare_mir_test_output(curr_test.unwrap(), &curr_test_contents);
+                    match self.compare_mir_test_output(curr_test.unwrap(), &curr_test_contents) {
+                        Ok(()) => blessed_lines.extend(curr_block_lines.drain(..)),
+                        Err(msg) if self.config.bless && curr_block_lines.iter().any(|l| {
+                            is_mir_elision(l)
+                        }) => {
+                            // Replacing the block with the whole dump would lose the parts
+                            // the author chose to elide.
+                            panic!("{}\n`--bless` doesn't update MIR blocks with `// ...` \
+                                    elisions; update the block of `{}` by hand",
+                                   msg, curr_test.unwrap());
+                        }
+                        Err(msg) if self.config.bless => {
+                            // Only the part of the dump the block used to check is written
+                            // back, so that blessing doesn't widen what the test checks.
+                            let dump = self.mir_dump_lines(curr_test.unwrap());
+                            let range = match mir_bless_range(&curr_test_contents, &dump) {
+                                Some(range) => range,
+                                None => panic!(
+                                    "{}\n`--bless` can't find the first line of the MIR block \
+                                     of `{}` in the dump anymore; update the block by hand",
+                                    msg, curr_test.unwrap()
+                                ),
+                            };
+                            blessed_lines.extend(dump[range].iter().map(|l| format!("// {}", l)));
+                            curr_block_lines.clear();
+                            blessed_any = true;
+                        }
+                        Err(msg) => panic!("{}", msg),
+                    }
+                    blessed_lines.push(l.to_owned());
                     curr_test = None;
                     curr_test_contents.clear();
                     curr_test_contents.push(ExpectedLine::Elision);
+                    continue;
                 } else if l.is_empty() {
                     // ignore
-                } else if l.starts_with("//") && l.split_at("//".len()).1.trim() == "..." {
+                } else if is_mir_elision(l) {
                     curr_test_contents.push(ExpectedLine::Elision)
                 } else if l.starts_with("// ") {
                     let (_, test_content) = l.split_at("// ".len());
                     curr_test_contents.push(ExpectedLine::Text(test_content));
                 }
+
+                if curr_test.is_some() {
+                    curr_block_lines.push(l.to_owned());
+                } else {
+                    blessed_lines.push(l.to_owned());
+                }
+            }
+
+            if blessed_any {
+                let mut blessed = String::from(source_text);
+                blessed.push_str(&blessed_lines.join("\n"));
+                if tests_text.ends_with('\n') {
+                    blessed.push('\n');
+                }
+                if let Err(err) = fs::write(&self.testpaths.file, blessed) {
+                    self.fatal(&format!(
+                        "failed to write blessed MIR to `{}`: {}",
+                        self.testpaths.file.display(),
+                        err,
+                    ));
+                }
+                record_blessed_file(BlessedFile::Updated(self.testpaths.file.clone()));
             }
         }
     }
@@ -2860,7 +2982,23 @@ impl<'test> TestCx<'test> {
         }
     }
 
-    fn compare_mir_test_output(&self, test_name: &str, expected_content: &[ExpectedLine<&str>]) {
+    /// The non-empty lines of the MIR dumped for `test_name`, without comments.
+    fn mir_dump_lines(&self, test_name: &str) -> Vec<String> {
+        let output_file = self.get_mir_dump_dir().join(test_name);
+        fs::read_to_string(&output_file)
+            .unwrap()
+            .lines()
+            .map(nocomment_mir_line)
+            .filter(|l| !l.is_empty())
+            .map(|l| l.to_owned())
+            .collect()
+    }
+
+    fn compare_mir_test_output(
+        &self,
+        test_name: &str,
+        expected_content: &[ExpectedLine<&str>],
+    ) -> Result<(), String> {
         let mut output_file = PathBuf::new();
         output_file.push(self.get_mir_dump_dir());
         output_file.push(test_name);
@@ -2914,14 +3052,14 @@ impl<'test> TestCx<'test> {
                 .map(|l| f(l))
                 .collect::<Vec<_>>()
                 .join("\n");
-            panic!(
+            format!(
                 "Did not find expected line, error: {}\n\
                  Expected Line: {:?}\n\
                  Test Name: {}\n\
                  Expected:\n{}\n\
                  Actual:\n{}",
                 extra_msg, expected_line, test_name, expected_content, normalize_all
-            );
+            )
         };
 
         // We expect each non-empty line to appear consecutively, non-consecutive lines
@@ -2937,7 +3075,7 @@ impl<'test> TestCx<'test> {
 
                     if !compare(expected_line, dumped_line) {
                         error!("{:?}", start_block_line);
-                        error(
+                        return Err(error(
                             expected_line,
                             format!(
                                 "Mismatch in lines\n\
@@ -2946,7 +3084,7 @@ impl<'test> TestCx<'test> {
                                 start_block_line.unwrap_or("None"),
                                 dumped_line
                             ),
-                        );
+                        ));
                     }
                 }
                 Some(&ExpectedLine::Elision) => {
@@ -2966,13 +3104,17 @@ impl<'test> TestCx<'test> {
                             }
                         }
                         if !found {
-                            error(expected_line, "ran out of mir dump to match against".into());
+                            return Err(error(
+                                expected_line,
+                                "ran out of mir dump to match against".into(),
+                            ));
                         }
                     }
                 }
                 None => {}
             }
         }
+        Ok(())
     }
 
     fn get_mir_dump_dir(&self) -> PathBuf {
@@ -3143,12 +3285,18 @@ impl<'test> TestCx<'test> {
 
         let mut files = vec![output_file];
         if self.config.bless {
-            files.push(expected_output_path(
+            let expected_file = expected_output_path(
                 self.testpaths,
                 self.revision,
                 &self.config.compare_mode,
                 kind,
-            ));
+            );
+            record_blessed_file(if actual.is_empty() {
+                BlessedFile::Deleted(expected_file.clone())
+            } else {
+                BlessedFile::Updated(expected_file.clone())
+            });
+            files.push(expected_file);
         }
 
         for output_file in &files {
@@ -3189,6 +3337,7 @@ impl<'test> TestCx<'test> {
 
         if examined_path.exists() && canon_content == &examined_content {
             self.delete_file(&examined_path);
+            record_blessed_file(BlessedFile::Deleted(examined_path));
         }
     }
 
@@ -3277,6 +3426,56 @@ where
     }
 }
 
+/// The expected output file of an exact pretty-printing test that `--bless` may update.
+///
+/// Only exact pretty-printing tests with a separate expected file have one: a bare
+/// `// pp-exact` expects the test itself, which must not be overwritten with the output of
+/// the pretty-printer.
+fn pretty_expected_file(test_file: &Path, pp_exact: &Option<PathBuf>) -> Option<PathBuf> {
+    pp_exact
+        .as_ref()
+        .map(|file| test_file.parent().unwrap().join(file))
+        .filter(|filepath| filepath != test_file)
+}
+
+/// The lines of `dump` that the MIR block `expected`, which has no `// ...` elisions besides
+/// the implicit leading one, covered: from the first dumped line matching its first line up to
+/// the line matching its last line that is closest to where the block used to end, or as many
+/// lines as the block has if that one is gone. Returns `None` if the first line isn't in the
+/// dump anymore.
+fn mir_bless_range(expected: &[ExpectedLine<&str>], dump: &[String]) -> Option<Range<usize>> {
+    let expected: Vec<_> = expected
+        .iter()
+        .filter_map(|l| match *l {
+            ExpectedLine::Text(t) if !t.is_empty() => Some(normalize_mir_line(t)),
+            _ => None,
+        })
+        .collect();
+    let (first, last) = match (expected.first(), expected.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Some(0..0),
+    };
+    let dump: Vec<_> = dump.iter().map(|l| normalize_mir_line(l)).collect();
+
+    let start = dump.iter().position(|l| l == first)?;
+    if expected.len() == 1 {
+        return Some(start..start + 1);
+    }
+    let old_last = start + expected.len() - 1;
+    let last_idx = (start + 1..dump.len())
+        .filter(|&idx| dump[idx] == *last)
+        .min_by_key(|&idx| if idx > old_last { idx - old_last } else { old_last - idx });
+    match last_idx {
+        Some(idx) => Some(start..idx + 1),
+        None => Some(start..cmp::min(start + expected.len(), dump.len())),
+    }
+}
+
+/// Whether `line` of a MIR test is a `// ...` elision.
+fn is_mir_elision(line: &str) -> bool {
+    line.starts_with("//") && line.split_at("//".len()).1.trim() == "..."
+}
+
 fn normalize_mir_line(line: &str) -> String {
     nocomment_mir_line(line).replace(char::is_whitespace, "")
 }
@@ -3378,3 +3577,42 @@ fn read2_abbreviated(mut child: Child) -> io::Result<Output> {
         stderr: stderr.into_bytes(),
     })
 }
+
+#[test]
+fn test_mir_bless_range() {
+    fn dump(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|l| l.to_string()).collect()
+    }
+    fn block(lines: &[&'static str]) -> Vec<ExpectedLine<&'static str>> {
+        let mut block = vec![ExpectedLine::Elision];
+        block.extend(lines.iter().map(|&l| ExpectedLine::Text(l)));
+        block
+    }
+
+    let mir = dump(&["fn main() -> () {", "    let mut _0: ();", "    bb0: {", "        _0 = ();",
+                     "        return;", "    }", "}"]);
+    // A block that checked part of the dump only gets that part back.
+    assert_eq!(mir_bless_range(&block(&["    bb0: {", "        _0 = const 1;", "    }"]), &mir),
+               Some(2..6));
+    // Lines added to or removed from the middle of the block are picked up.
+    assert_eq!(mir_bless_range(&block(&["    bb0: {", "    }"]), &mir), Some(2..6));
+    assert_eq!(mir_bless_range(&block(&["fn main() -> () {", "    let mut _0: ();", "    let x;",
+                                        "    let y;", "    bb0: {"]), &mir),
+               Some(0..3));
+    // Without its last line, the block keeps its length.
+    assert_eq!(mir_bless_range(&block(&["fn main() -> () {", "    let mut _0: u8;"]), &mir),
+               Some(0..2));
+    assert_eq!(mir_bless_range(&block(&["        return;", "        x;", "        y;"]), &mir),
+               Some(4..7));
+    // A block whose first line is gone can't be located.
+    assert_eq!(mir_bless_range(&block(&["fn foo() -> () {", "}"]), &mir), None);
+}
+
+#[test]
+fn test_pretty_expected_file() {
+    let test_file = Path::new("src/test/pretty/foo.rs");
+    assert_eq!(pretty_expected_file(test_file, &None), None);
+    assert_eq!(pretty_expected_file(test_file, &Some(PathBuf::from("foo.rs"))), None);
+    assert_eq!(pretty_expected_file(test_file, &Some(PathBuf::from("foo.pp"))),
+               Some(PathBuf::from("src/test/pretty/foo.pp")));
+}